@@ -0,0 +1,108 @@
+use crate::rules::*;
+use crate::word_list::*;
+
+// What came of a looped word the player finished tracing
+#[derive(Clone, Debug, PartialEq)]
+pub enum SolveAttempt {
+    // the ring's own word
+    Perfect(String),
+    // another dictionary word that fits the ring just as well
+    Alternate(String),
+    Rejected { word: String, reason: RejectReason },
+}
+
+impl SolveAttempt {
+    // None while the player is still tracing, otherwise whether `built_word` solves the ring for `target`
+    pub fn check(built_word: &str, target: &str, rules: &SelectionRules, word_list: &WordList) -> Option<SolveAttempt> {
+        let ring_size = target.len() - 1;
+        let word = rules.finished_word(built_word, ring_size)?;
+
+        let attempt = if word.eq_ignore_ascii_case(target) {
+            SolveAttempt::Perfect(word)
+        }
+        else if let Err(violation) = rules.check_word(&word, ring_size) {
+            SolveAttempt::Rejected { word, reason: RejectReason::BrokenRule(violation) }
+        }
+        else if word_list.contains(&word) {
+            SolveAttempt::Alternate(word)
+        }
+        else {
+            SolveAttempt::Rejected { word, reason: RejectReason::NotAWord }
+        };
+
+        Some(attempt)
+    }
+
+    // A tap the rules refused. On a full length word it was a failed attempt to close the loop and
+    // counts as a rejected word, anywhere else the rune is just left out.
+    pub fn refused_tap(built_word: &str, letter: char, ring_size: usize, violation: RuleViolation) -> Option<SolveAttempt> {
+        if built_word.len() < ring_size {
+            return None;
+        }
+
+        Some(SolveAttempt::Rejected { word: format!("{}{}", built_word, letter), reason: RejectReason::BrokenRule(violation) })
+    }
+
+    // No word starts the way the player has traced, so there's no point carrying on
+    pub fn is_dead_end(built_word: &str, target: &str, word_list: &WordList) -> bool {
+        let on_target = target.to_ascii_uppercase().starts_with(&built_word.to_ascii_uppercase());
+        !on_target && !word_list.is_prefix(built_word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word_list() -> WordList {
+        WordList::from_text("threat hearth rather", &SelectionRules::default())
+    }
+
+    #[test]
+    fn unfinished_words_are_not_checked() {
+        assert_eq!(SolveAttempt::check("THRE", "threat", &SelectionRules::default(), &word_list()), None);
+    }
+
+    #[test]
+    fn the_target_is_a_perfect_solve() {
+        let attempt = SolveAttempt::check("THREAT", "threat", &SelectionRules::default(), &word_list());
+
+        assert_eq!(attempt, Some(SolveAttempt::Perfect("THREAT".to_string())));
+    }
+
+    #[test]
+    fn other_loops_are_alternates_or_rejected() {
+        let rules = SelectionRules::default();
+
+        assert_eq!(SolveAttempt::check("HEARTH", "threat", &rules, &word_list()), Some(SolveAttempt::Alternate("HEARTH".to_string())));
+        assert_eq!(
+            SolveAttempt::check("TAREHT", "threat", &rules, &word_list()),
+            Some(SolveAttempt::Rejected { word: "TAREHT".to_string(), reason: RejectReason::NotAWord })
+        );
+    }
+
+    #[test]
+    fn loops_close_themselves_when_the_rules_say_so() {
+        let rules = SelectionRules { close_loop: false, ..Default::default() };
+
+        assert_eq!(SolveAttempt::check("RATHE", "threat", &rules, &word_list()), Some(SolveAttempt::Alternate("RATHER".to_string())));
+    }
+
+    #[test]
+    fn refused_taps_only_count_on_a_full_length_word() {
+        let violation = RuleViolation::ReusedRune('T');
+
+        assert_eq!(SolveAttempt::refused_tap("THR", 'T', 5, violation), None);
+        assert_eq!(
+            SolveAttempt::refused_tap("THREA", 'H', 5, RuleViolation::ReusedRune('H')),
+            Some(SolveAttempt::Rejected { word: "THREAH".to_string(), reason: RejectReason::BrokenRule(RuleViolation::ReusedRune('H')) })
+        );
+    }
+
+    #[test]
+    fn dead_ends_leave_the_target_and_every_word() {
+        assert!(!SolveAttempt::is_dead_end("THR", "threat", &word_list()));
+        assert!(!SolveAttempt::is_dead_end("HEA", "threat", &word_list()));
+        assert!(SolveAttempt::is_dead_end("HT", "threat", &word_list()));
+    }
+}
@@ -0,0 +1,18 @@
+// The rules of Summoners Word without any engine: rings and how their runes are laid out, the word
+// list, the selection rules, checking a traced word and validating puzzle packs. The game's systems call into this, and it
+// can be used from tools or other front ends as is.
+
+mod attempt;
+mod pack;
+mod puzzle;
+mod rules;
+mod validation;
+mod word_list;
+
+pub use attempt::*;
+pub use pack::*;
+pub use puzzle::*;
+pub use rules::*;
+pub use validation::*;
+pub use word_list::*;
+pub use word_utils::*;
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::puzzle::*;
+use crate::rules::*;
+use crate::validation::*;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "bevy", derive(bevy_asset::Asset, bevy_reflect::TypePath))]
+pub struct PuzzlePack {
+    #[serde(default)]
+    pub rules: SelectionRules,
+    pub levels: Vec<Puzzle>,
+}
+
+#[derive(Debug, Error)]
+pub enum PuzzlePackError {
+    #[error("could not read puzzle pack: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse puzzle pack: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("puzzle pack is invalid:{}", .0.iter().map(|issue| format!("\n  {}", issue)).collect::<String>())]
+    Invalid(Vec<PuzzleIssue>),
+}
+
+impl PuzzlePack {
+    // Parses a pack and refuses it unless every level can be played, `demon_count` is how many demons
+    // the front end has to show
+    pub fn from_ron(bytes: &[u8], demon_count: usize) -> Result<PuzzlePack, PuzzlePackError> {
+        let pack = ron::de::from_bytes::<PuzzlePack>(bytes)?;
+
+        let issues = validate_pack(&pack, demon_count);
+        if !issues.is_empty() {
+            return Err(PuzzlePackError::Invalid(issues));
+        }
+
+        Ok(pack)
+    }
+}
@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use word_utils::*;
+
+use crate::rules::*;
+use crate::validation::*;
+use crate::word_list::*;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Ring {
+    pub word: String,
+    pub step: usize,
+    pub start: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Puzzle {
+    pub demon: usize,
+    pub rings: Vec<Ring>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, String>,
+}
+
+// The runes of a ring in the order they sit around it, slot 0 on the right going anticlockwise
+#[derive(Clone, Debug, PartialEq)]
+pub struct RingLayout {
+    pub letters: Vec<char>,
+}
+
+impl Ring {
+    // The word's letters are scattered by `step` and the ring is read starting from slot `start`.
+    // The closing letter is not part of the ring. Only valid rings can be laid out, any other step
+    // or start would leave slots empty.
+    pub fn layout(&self) -> Result<RingLayout, Vec<PuzzleProblem>> {
+        let problems = validate_ring(self);
+        if !problems.is_empty() {
+            return Err(problems);
+        }
+
+        let bytes = self.word.as_bytes();
+        let length = bytes.len() - 1;
+
+        let mut shuffled_word = vec![0; length];
+
+        for (i, letter) in bytes[..length].iter().enumerate() {
+            let shuffled_index = (i * self.step) % length;
+            shuffled_word[shuffled_index] = *letter;
+        }
+
+        let letters = (0..length)
+            .map(|i| (shuffled_word[(i + self.start) % length] as char).to_ascii_uppercase())
+            .collect();
+
+        Ok(RingLayout { letters })
+    }
+}
+
+impl Puzzle {
+    // An untitled level from (word, step, start) rings, for tests and tools
+    pub fn from_rings(demon: usize, rings: &[(&str, usize, usize)]) -> Puzzle {
+        Puzzle {
+            demon,
+            rings: rings.iter().map(|(word, step, start)| Ring { word: word.to_string(), step: *step, start: *start }).collect(),
+            title: None,
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+impl RingLayout {
+    pub fn new(word: &str, step: usize, start: usize) -> Result<RingLayout, Vec<PuzzleProblem>> {
+        Ring { word: word.to_string(), step, start }.layout()
+    }
+
+    pub fn len(&self) -> usize {
+        self.letters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    // Angle in radians of the rune in `slot`, the runes are spread evenly around the circle
+    pub fn angle(&self, slot: usize) -> f32 {
+        slot as f32 * (2. * PI / self.len() as f32)
+    }
+
+    // Ring slots to tap, in order, to spell `word`, or None if a letter isn't on the ring
+    pub fn trace(&self, word: &str) -> Option<Vec<usize>> {
+        word.chars()
+            .map(|c| self.letters.iter().position(|letter| letter.eq_ignore_ascii_case(&c)))
+            .collect()
+    }
+
+    // Every dictionary word the player could complete the ring with: made only of the ring's
+    // runes and traced the way the selection rules allow. Only the ambiguity report lists them,
+    // play checks each traced word as it's finished with SolveAttempt::check.
+    pub fn solutions(&self, word_list: &WordList, rules: &SelectionRules) -> Vec<String> {
+        let ring_bits = word_to_bits(&self.letters.iter().collect::<String>());
+
+        word_list.words_using(ring_bits).into_iter()
+            .filter(|word| self.trace(word).is_some())
+            .filter(|word| rules.check_word(word, self.len()).is_ok())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letters_scatter_by_step() {
+        // M, A, Y, H, E land in slots 0, 3, 6 % 5 = 1, 9 % 5 = 4, 12 % 5 = 2
+        assert_eq!(RingLayout::new("mayhem", 3, 0).unwrap().letters, vec!['M', 'Y', 'E', 'A', 'H']);
+        assert_eq!(RingLayout::new("mayhem", 3, 1).unwrap().letters, vec!['Y', 'E', 'A', 'H', 'M']);
+    }
+
+    #[test]
+    fn runes_are_spread_evenly() {
+        let layout = RingLayout::new("threat", 1, 0).unwrap();
+
+        assert_eq!(layout.angle(0), 0.);
+        assert!((layout.angle(1) - 2. * PI / 5.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn solver_finds_anagram_loops() {
+        let word_list = WordList::from_text("hearth rather threat teeth mayhem", &SelectionRules::default());
+        let layout = RingLayout::new("threat", 3, 3).unwrap();
+
+        let mut solutions = layout.solutions(&word_list, &SelectionRules::default());
+        solutions.sort();
+
+        assert_eq!(solutions, vec!["HEARTH", "RATHER", "THREAT"]);
+    }
+
+    #[test]
+    fn reuse_rules_accept_longer_words() {
+        let once_rules = SelectionRules::default();
+        let reuse_rules = SelectionRules { rune_usage: RuneUsage::Reuse, ..Default::default() };
+        let layout = RingLayout::new("threat", 3, 3).unwrap();
+
+        let once = layout.solutions(&WordList::from_text("threat teetht", &once_rules), &once_rules);
+        let reuse = layout.solutions(&WordList::from_text("threat teetht", &reuse_rules), &reuse_rules);
+
+        assert!(!once.contains(&"TEETHT".to_string()));
+        assert!(reuse.contains(&"TEETHT".to_string()));
+    }
+
+    #[test]
+    fn trace_rejects_missing_letters() {
+        let layout = RingLayout::new("mayhem", 3, 1).unwrap();
+
+        assert_eq!(layout.trace("hymn"), None);
+        assert_eq!(layout.trace("mayhem"), Some(vec![4, 2, 0, 3, 1, 4]));
+    }
+
+    #[test]
+    fn invalid_rings_have_no_layout() {
+        assert_eq!(RingLayout::new("", 1, 0), Err(vec![PuzzleProblem::TooShort { length: 0 }]));
+        assert_eq!(RingLayout::new("mayhem", 5, 0), Err(vec![PuzzleProblem::StepNotCoprime { step: 5, length: 5 }]));
+        assert_eq!(RingLayout::new("mayhem", 1, 5), Err(vec![PuzzleProblem::StartOutOfRange { start: 5, length: 5 }]));
+    }
+}
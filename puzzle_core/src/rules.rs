@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use word_utils::*;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum RuneUsage {
+    // every rune on the ring is used once, only the starting rune is tapped again to close
+    #[default]
+    ExactlyOnce,
+    // runes can be tapped any number of times
+    Reuse,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(bevy_ecs::system::Resource))]
+pub struct SelectionRules {
+    #[serde(default)]
+    pub rune_usage: RuneUsage,
+    // when false the loop closes itself once the word is otherwise finished
+    #[serde(default = "close_loop_default")]
+    pub close_loop: bool,
+    // seconds a trace no word starts with stays on screen before it's cleared, None leaves it to the player
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clear_dead_ends_after: Option<f32>,
+}
+
+fn close_loop_default() -> bool {
+    true
+}
+
+impl Default for SelectionRules {
+    fn default() -> Self {
+        SelectionRules {
+            rune_usage: RuneUsage::ExactlyOnce,
+            close_loop: close_loop_default(),
+            clear_dead_ends_after: None,
+        }
+    }
+}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq)]
+pub enum RuleViolation {
+    #[error("rune {0} has already been used")]
+    ReusedRune(char),
+    #[error("the loop is already closed")]
+    LoopClosed,
+    #[error("the word has to end on the rune it started from")]
+    LoopNotClosed,
+    #[error("the word is {length} letters long but the ring needs {expected}")]
+    WrongLength { length: usize, expected: usize },
+}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq)]
+pub enum RejectReason {
+    #[error("not a word")]
+    NotAWord,
+    #[error("{0}")]
+    BrokenRule(RuleViolation),
+}
+
+impl SelectionRules {
+    // Whether a dictionary word could ever be played under these rules, words repeating a letter
+    // only fit a ring when runes can be tapped again
+    pub fn allows_word(&self, word: &str) -> bool {
+        match self.rune_usage {
+            RuneUsage::ExactlyOnce => is_valid_word(word),
+            RuneUsage::Reuse => is_loop_word(word),
+        }
+    }
+
+    // Whether `letter` may be added to the word traced so far on a ring of `ring_size` runes
+    pub fn check_tap(&self, built_word: &str, letter: char, ring_size: usize) -> Result<(), RuleViolation> {
+        match self.rune_usage {
+            RuneUsage::Reuse => Ok(()),
+            RuneUsage::ExactlyOnce => {
+                if built_word.len() > ring_size || (!self.close_loop && built_word.len() == ring_size) {
+                    return Err(RuleViolation::LoopClosed);
+                }
+
+                let used = built_word.chars().any(|c| c.eq_ignore_ascii_case(&letter));
+                let closes_loop = self.close_loop
+                    && built_word.len() == ring_size
+                    && built_word.chars().next().is_some_and(|c| c.eq_ignore_ascii_case(&letter));
+
+                if used && !closes_loop {
+                    Err(RuleViolation::ReusedRune(letter))
+                }
+                else {
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    // The full looped word once the player has traced enough to check it, None while still tracing
+    pub fn finished_word(&self, built_word: &str, ring_size: usize) -> Option<String> {
+        let first = built_word.chars().next()?;
+
+        let word = if self.close_loop {
+            built_word.to_string()
+        }
+        else {
+            format!("{}{}", built_word, first)
+        };
+
+        let long_enough = match self.rune_usage {
+            RuneUsage::ExactlyOnce => word.len() == ring_size + 1,
+            RuneUsage::Reuse => word.len() > ring_size,
+        };
+
+        let closed = word.chars().last().is_some_and(|last| last.eq_ignore_ascii_case(&first));
+
+        if long_enough && closed { Some(word) } else { None }
+    }
+
+    // Whether a complete looped word obeys the rules on a ring of `ring_size` runes
+    pub fn check_word(&self, word: &str, ring_size: usize) -> Result<(), RuleViolation> {
+        let bytes = word.as_bytes();
+        let expected = ring_size + 1;
+
+        let length_ok = match self.rune_usage {
+            RuneUsage::ExactlyOnce => bytes.len() == expected,
+            RuneUsage::Reuse => bytes.len() >= expected,
+        };
+        if !length_ok {
+            return Err(RuleViolation::WrongLength { length: bytes.len(), expected });
+        }
+
+        if !bytes[0].eq_ignore_ascii_case(&bytes[bytes.len() - 1]) {
+            return Err(RuleViolation::LoopNotClosed);
+        }
+
+        if self.rune_usage == RuneUsage::ExactlyOnce {
+            let interior = &bytes[..bytes.len() - 1];
+            for (i, c) in interior.iter().enumerate() {
+                if interior[..i].iter().any(|earlier| earlier.eq_ignore_ascii_case(c)) {
+                    return Err(RuleViolation::ReusedRune(c.to_ascii_uppercase() as char));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
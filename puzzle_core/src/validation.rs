@@ -0,0 +1,221 @@
+use std::fmt;
+use thiserror::Error;
+use word_utils::*;
+
+use crate::pack::*;
+use crate::puzzle::*;
+
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum PuzzleProblem {
+    #[error("pack has no levels")]
+    NoLevels,
+    #[error("level has no rings")]
+    NoRings,
+    #[error("demon {demon} does not exist, only {demon_count} demons are available")]
+    UnknownDemon { demon: usize, demon_count: usize },
+    #[error("word is {length} letters long, a ring needs at least {} runes and the closing letter", MIN_RING_RUNES)]
+    TooShort { length: usize },
+    #[error("word contains '{letter}', rings can only use the letters A to Z")]
+    NotAlphabetic { letter: char },
+    #[error("word starts with '{first}' but ends with '{last}', so it can't close the loop")]
+    OpenLoop { first: char, last: char },
+    #[error("letter '{letter}' appears more than once before the closing letter")]
+    RepeatedLetter { letter: char },
+    #[error("step {step} is not coprime with ring length {length}, letters would collide")]
+    StepNotCoprime { step: usize, length: usize },
+    #[error("start {start} is out of range for ring length {length}")]
+    StartOutOfRange { start: usize, length: usize },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PuzzleIssue {
+    pub level: Option<usize>,
+    pub ring: Option<(usize, String)>,
+    pub problem: PuzzleProblem,
+}
+
+impl fmt::Display for PuzzleIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.level, &self.ring) {
+            (Some(level), Some((ring, word))) => write!(f, "level {} ring {} \"{}\": {}", level, ring, word, self.problem),
+            (Some(level), None) => write!(f, "level {}: {}", level, self.problem),
+            _ => write!(f, "{}", self.problem),
+        }
+    }
+}
+
+pub fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+pub fn validate_ring(ring: &Ring) -> Vec<PuzzleProblem> {
+    let mut problems = Vec::new();
+    let bytes = ring.word.as_bytes();
+
+    if let Some(letter) = ring.word.chars().find(|c| !c.is_ascii_alphabetic()) {
+        // nothing below makes sense for words the ring can't display
+        problems.push(PuzzleProblem::NotAlphabetic { letter });
+        return problems;
+    }
+
+    if bytes.len() <= MIN_RING_RUNES {
+        problems.push(PuzzleProblem::TooShort { length: bytes.len() });
+        return problems;
+    }
+
+    let length = bytes.len() - 1;
+
+    let first = bytes[0].to_ascii_uppercase() as char;
+    let last = bytes[length].to_ascii_uppercase() as char;
+    if first != last {
+        problems.push(PuzzleProblem::OpenLoop { first, last });
+    }
+
+    let mut seen = 0u32;
+    for (i, c) in bytes[..length].iter().enumerate() {
+        let bit = word_to_bits(&ring.word[i..i + 1]);
+        if seen & bit != 0 {
+            let letter = c.to_ascii_uppercase() as char;
+            if !problems.contains(&PuzzleProblem::RepeatedLetter { letter }) {
+                problems.push(PuzzleProblem::RepeatedLetter { letter });
+            }
+        }
+        seen |= bit;
+    }
+
+    if ring.step == 0 || gcd(ring.step, length) != 1 {
+        problems.push(PuzzleProblem::StepNotCoprime { step: ring.step, length });
+    }
+
+    if ring.start >= length {
+        problems.push(PuzzleProblem::StartOutOfRange { start: ring.start, length });
+    }
+
+    problems
+}
+
+pub fn validate_level(level_index: usize, level: &Puzzle, demon_count: usize) -> Vec<PuzzleIssue> {
+    let mut issues = Vec::new();
+
+    if level.demon >= demon_count {
+        issues.push(PuzzleIssue {
+            level: Some(level_index),
+            ring: None,
+            problem: PuzzleProblem::UnknownDemon { demon: level.demon, demon_count },
+        });
+    }
+
+    if level.rings.is_empty() {
+        issues.push(PuzzleIssue {
+            level: Some(level_index),
+            ring: None,
+            problem: PuzzleProblem::NoRings,
+        });
+    }
+
+    for (ring_index, ring) in level.rings.iter().enumerate() {
+        for problem in validate_ring(ring) {
+            issues.push(PuzzleIssue {
+                level: Some(level_index),
+                ring: Some((ring_index, ring.word.clone())),
+                problem,
+            });
+        }
+    }
+
+    issues
+}
+
+pub fn validate_pack(pack: &PuzzlePack, demon_count: usize) -> Vec<PuzzleIssue> {
+    if pack.levels.is_empty() {
+        return vec![PuzzleIssue { level: None, ring: None, problem: PuzzleProblem::NoLevels }];
+    }
+
+    pack.levels.iter()
+        .enumerate()
+        .flat_map(|(index, level)| validate_level(index, level, demon_count))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ring_problems(word: &str, step: usize, start: usize) -> Vec<PuzzleProblem> {
+        validate_ring(&Ring { word: word.to_string(), step, start })
+    }
+
+    fn pack(levels: Vec<Puzzle>) -> PuzzlePack {
+        PuzzlePack { rules: Default::default(), levels }
+    }
+
+    #[test]
+    fn valid_rings_have_no_problems() {
+        assert_eq!(ring_problems("mayhem", 3, 1), vec![]);
+        assert_eq!(validate_pack(&pack(vec![Puzzle::from_rings(0, &[("mayhem", 3, 1), ("threat", 3, 3)])]), 1), vec![]);
+    }
+
+    #[test]
+    fn packs_need_levels() {
+        let issues = validate_pack(&pack(vec![]), 1);
+
+        assert_eq!(issues, vec![PuzzleIssue { level: None, ring: None, problem: PuzzleProblem::NoLevels }]);
+    }
+
+    #[test]
+    fn levels_need_rings() {
+        let issues = validate_pack(&pack(vec![Puzzle::from_rings(0, &[])]), 1);
+
+        assert_eq!(issues, vec![PuzzleIssue { level: Some(0), ring: None, problem: PuzzleProblem::NoRings }]);
+    }
+
+    #[test]
+    fn demons_must_exist() {
+        let issues = validate_level(2, &Puzzle::from_rings(5, &[("mayhem", 3, 1)]), 5);
+
+        assert_eq!(issues, vec![PuzzleIssue { level: Some(2), ring: None, problem: PuzzleProblem::UnknownDemon { demon: 5, demon_count: 5 } }]);
+    }
+
+    #[test]
+    fn short_words_are_refused() {
+        assert_eq!(ring_problems("sass", 1, 0), vec![PuzzleProblem::TooShort { length: 4 }]);
+    }
+
+    #[test]
+    fn only_letters_fit_on_a_ring() {
+        assert_eq!(ring_problems("may-hem", 1, 0), vec![PuzzleProblem::NotAlphabetic { letter: '-' }]);
+    }
+
+    #[test]
+    fn words_must_close_the_loop() {
+        assert_eq!(ring_problems("mayhew", 1, 0), vec![PuzzleProblem::OpenLoop { first: 'M', last: 'W' }]);
+        // the closing letter may differ in case
+        assert_eq!(ring_problems("Mayhem", 1, 0), vec![]);
+    }
+
+    #[test]
+    fn letters_before_the_close_are_distinct() {
+        assert_eq!(ring_problems("teetht", 1, 0), vec![PuzzleProblem::RepeatedLetter { letter: 'E' }, PuzzleProblem::RepeatedLetter { letter: 'T' }]);
+    }
+
+    #[test]
+    fn steps_must_be_coprime_with_the_ring() {
+        assert_eq!(ring_problems("lethal", 5, 0), vec![PuzzleProblem::StepNotCoprime { step: 5, length: 5 }]);
+        assert_eq!(ring_problems("lethal", 0, 0), vec![PuzzleProblem::StepNotCoprime { step: 0, length: 5 }]);
+    }
+
+    #[test]
+    fn starts_must_be_on_the_ring() {
+        assert_eq!(ring_problems("lethal", 1, 5), vec![PuzzleProblem::StartOutOfRange { start: 5, length: 5 }]);
+    }
+
+    #[test]
+    fn issues_name_the_level_and_ring() {
+        let issues = validate_pack(&pack(vec![Puzzle::from_rings(0, &[("mayhem", 3, 1)]), Puzzle::from_rings(0, &[("mayhem", 3, 1), ("lethal", 1, 5)])]), 1);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].to_string(), "level 1 ring 1 \"lethal\": start 5 is out of range for ring length 5");
+        assert_eq!(PuzzleIssue { level: Some(3), ring: None, problem: PuzzleProblem::NoRings }.to_string(), "level 3: level has no rings");
+        assert_eq!(PuzzleIssue { level: None, ring: None, problem: PuzzleProblem::NoLevels }.to_string(), "pack has no levels");
+    }
+}
@@ -0,0 +1,142 @@
+use word_utils::*;
+
+use crate::rules::*;
+
+#[derive(Clone, Default)]
+struct TrieNode {
+    // index of the child node for each letter, 0 where there is none since the root is never a child
+    children: [u32; 26],
+    is_word: bool,
+}
+
+// Prefix tree of every accepted word, so lookups cost the length of the word rather than the size of the list
+#[cfg_attr(feature = "bevy", derive(bevy_ecs::system::Resource))]
+pub struct WordList {
+    nodes: Vec<TrieNode>,
+}
+
+impl WordList {
+    pub fn empty() -> WordList {
+        WordList {
+            nodes: vec![TrieNode::default()],
+        }
+    }
+
+    // One word per line or separated by any whitespace, keeping only those the rules let the player trace
+    pub fn from_text(contents: &str, rules: &SelectionRules) -> WordList {
+        let mut list = WordList::empty();
+
+        for word in contents.split_whitespace() {
+            if rules.allows_word(word) {
+                list.insert(word);
+            }
+        }
+
+        list
+    }
+
+    pub fn insert(&mut self, word: &str) {
+        let mut node = 0;
+
+        for c in word.as_bytes() {
+            let letter = alphabet_index(*c);
+            let mut child = self.nodes[node].children[letter] as usize;
+
+            if child == 0 {
+                child = self.nodes.len();
+                self.nodes.push(TrieNode::default());
+                self.nodes[node].children[letter] = child as u32;
+            }
+
+            node = child;
+        }
+
+        self.nodes[node].is_word = true;
+    }
+
+    fn find_node(&self, prefix: &str) -> Option<usize> {
+        let mut node = 0;
+
+        for c in prefix.as_bytes() {
+            if !c.is_ascii_alphabetic() {
+                return None;
+            }
+
+            node = match self.nodes[node].children[alphabet_index(*c)] {
+                0 => return None,
+                child => child as usize,
+            };
+        }
+
+        Some(node)
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.find_node(word).is_some_and(|node| self.nodes[node].is_word)
+    }
+
+    // Whether any word starts with `prefix`, including the prefix itself being a word
+    pub fn is_prefix(&self, prefix: &str) -> bool {
+        self.find_node(prefix).is_some()
+    }
+
+    // Every word made only of the letters set in `letter_bits`, see `word_to_bits`
+    pub fn words_using(&self, letter_bits: u32) -> Vec<String> {
+        let mut words = Vec::new();
+        let mut prefix = String::new();
+        self.collect_words(0, letter_bits, &mut prefix, &mut words);
+        words
+    }
+
+    fn collect_words(&self, node: usize, letter_bits: u32, prefix: &mut String, words: &mut Vec<String>) {
+        if self.nodes[node].is_word {
+            words.push(prefix.clone());
+        }
+
+        for (letter, child) in self.nodes[node].children.iter().enumerate() {
+            if *child != 0 && letter_bits & (1 << letter) != 0 {
+                prefix.push((b'A' + letter as u8) as char);
+                self.collect_words(*child as usize, letter_bits, prefix, words);
+                prefix.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trie_finds_words_and_prefixes() {
+        let list = WordList::from_text("mayhem\nthreat\nhearth\ncat\nrocks", &SelectionRules::default());
+
+        assert!(list.contains("MAYHEM"));
+        assert!(list.contains("threat"));
+        assert!(!list.contains("THREA"));
+        assert!(list.is_prefix("THREA"));
+        assert!(!list.is_prefix("THREAD"));
+        // too short to loop, and not closed
+        assert!(!list.contains("CAT"));
+        assert!(!list.contains("ROCKS"));
+    }
+
+    #[test]
+    fn words_using_only_picks_from_the_given_letters() {
+        let list = WordList::from_text("threat hearth rather mayhem", &SelectionRules::default());
+
+        let mut words = list.words_using(word_to_bits("threa"));
+        words.sort();
+
+        assert_eq!(words, vec!["HEARTH", "RATHER", "THREAT"]);
+    }
+
+    #[test]
+    fn the_rules_decide_which_words_load() {
+        let once = WordList::from_text("threat teetht", &SelectionRules::default());
+        let reuse = WordList::from_text("threat teetht", &SelectionRules { rune_usage: RuneUsage::Reuse, ..Default::default() });
+
+        assert!(once.contains("THREAT") && !once.contains("TEETHT"));
+        assert!(reuse.contains("THREAT") && reuse.contains("TEETHT"));
+    }
+}
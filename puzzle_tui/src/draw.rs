@@ -0,0 +1,78 @@
+use puzzle_core::*;
+
+// Ring radii in terminal rows, spaced wider than the game's so runes on neighbouring rings don't share a cell
+const BASE_RADIUS: f32 = 4.;
+const RING_SPACING: f32 = 2.5;
+// a terminal cell is about twice as tall as it is wide
+const CELL_ASPECT: f32 = 2.;
+
+const ACTIVE_RING: char = '*';
+const IDLE_RING: char = '.';
+
+fn ring_radius(ring: usize) -> f32 {
+    BASE_RADIUS + ring as f32 * RING_SPACING
+}
+
+// Rings drawn as concentric circles with each rune placed at the angle the game puts it. Runes on the
+// active ring are upper case and the rest lower case, as they are on screen.
+pub fn draw_rings(layouts: &[RingLayout], active: usize) -> Vec<String> {
+    let outer = ring_radius(layouts.len().saturating_sub(1)).ceil() as usize;
+    let (width, height) = (outer * 4 + 1, outer * 2 + 1);
+    let (center_x, center_y) = ((width / 2) as f32, (height / 2) as f32);
+
+    let mut grid = vec![vec![' '; width]; height];
+    let mut plot = |angle: f32, radius: f32, c: char| {
+        // rows count downwards but angles go anticlockwise from the right, like the game's y up
+        let x = (center_x + angle.cos() * radius * CELL_ASPECT).round() as usize;
+        let y = (center_y - angle.sin() * radius).round() as usize;
+        grid[y][x] = c;
+    };
+
+    for ring in 0..layouts.len() {
+        let radius = ring_radius(ring);
+        let mark = if ring == active { ACTIVE_RING } else { IDLE_RING };
+        let steps = (radius * 12.) as usize;
+
+        for step in 0..steps {
+            plot(step as f32 * std::f32::consts::TAU / steps as f32, radius, mark);
+        }
+    }
+
+    for (ring, layout) in layouts.iter().enumerate() {
+        for (slot, letter) in layout.letters.iter().enumerate() {
+            let letter = if ring == active { *letter } else { letter.to_ascii_lowercase() };
+            plot(layout.angle(slot), ring_radius(ring), letter);
+        }
+    }
+
+    grid.into_iter()
+        .map(|row| row.into_iter().collect::<String>().trim_end().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runes_sit_around_the_ring_from_the_right() {
+        let lines = draw_rings(&[RingLayout::new("mayhem", 3, 1).unwrap()], 0);
+
+        // Y, E, A, H, M going anticlockwise, the first rune on the far right of the middle row
+        assert_eq!(lines.len(), 9);
+        assert!(lines[4].ends_with('Y'));
+        assert!(lines.iter().take(4).any(|line| line.contains('E')));
+        assert!(lines.iter().skip(5).any(|line| line.contains('M')));
+    }
+
+    #[test]
+    fn only_the_active_ring_is_upper_case() {
+        let layouts = [RingLayout::new("mayhem", 3, 1).unwrap(), RingLayout::new("threat", 3, 3).unwrap()];
+        let text = draw_rings(&layouts, 1).join("\n");
+
+        for letter in ['y', 'e', 'a', 'h', 'm', 'T', 'H', 'R', 'E', 'A'] {
+            assert!(text.contains(letter), "{} missing from\n{}", letter, text);
+        }
+        assert!(!text.contains('Y'));
+    }
+}
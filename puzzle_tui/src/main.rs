@@ -0,0 +1,128 @@
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use puzzle_core::*;
+use std::io::{self, Write};
+
+use crate::draw::*;
+use crate::session::*;
+
+mod draw;
+mod session;
+
+// Plays puzzle packs in a terminal, for trying levels out over ssh:
+//   puzzle_tui [pack.ron] [--level <n>]
+// Without a pack it plays the one shipped with the game.
+
+const DEFAULT_PACK: &[u8] = include_bytes!("../../assets/puzzles/default.puzzles.ron");
+// the terminal doesn't draw the demons, so any demon will do
+const DEMON_COUNT: usize = usize::MAX;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    if let Err(err) = run(args.get(1..).unwrap_or_default()) {
+        println!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let mut pack_path = None;
+    let mut level = 0;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--level" {
+            let value = iter.next().ok_or("--level needs a value")?;
+            let number: usize = value.parse().map_err(|_| format!("--level expects a number, got {}", value))?;
+            level = number.saturating_sub(1);
+        }
+        else {
+            pack_path = Some(arg.clone());
+        }
+    }
+
+    let bytes = match &pack_path {
+        Some(path) => std::fs::read(path).map_err(|err| format!("could not read {}: {}", path, err))?,
+        None => DEFAULT_PACK.to_vec(),
+    };
+    // checked before the terminal goes into raw mode, so a bad pack can't leave it there
+    let pack = PuzzlePack::from_ron(&bytes, DEMON_COUNT).map_err(|err| err.to_string())?;
+
+    if level >= pack.levels.len() {
+        return Err(format!("the pack only has {} levels", pack.levels.len()));
+    }
+
+    let word_list = WordList::from_text(include_str!("../../src/words/filtered.txt"), &pack.rules);
+
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode().map_err(|err| err.to_string())?;
+    execute!(stdout, EnterAlternateScreen, Hide).map_err(|err| err.to_string())?;
+
+    let result = play(&mut stdout, &pack, &word_list, level);
+
+    // put the terminal back even if drawing failed part way
+    let _ = execute!(stdout, Show, LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+
+    result.map_err(|err| err.to_string())
+}
+
+fn play(stdout: &mut io::Stdout, pack: &PuzzlePack, word_list: &WordList, mut level: usize) -> io::Result<()> {
+    let mut session = Session::new(pack.levels[level].clone(), pack.rules, word_list);
+
+    loop {
+        render(stdout, &session, level, pack.levels.len())?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        // some terminals report releases too
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Enter if session.is_complete() => {
+                level += 1;
+                if level == pack.levels.len() {
+                    return Ok(());
+                }
+                session = Session::new(pack.levels[level].clone(), pack.rules, word_list);
+            }
+            KeyCode::Char(c) => session.type_letter(c),
+            KeyCode::Backspace => session.backspace(),
+            KeyCode::Esc => session.reset(),
+            _ => {}
+        }
+    }
+}
+
+fn render(stdout: &mut io::Stdout, session: &Session, level: usize, level_count: usize) -> io::Result<()> {
+    let title = session.puzzle.title.clone().unwrap_or_default();
+    let mut lines = vec![format!("Summoners Word {}/{}  {}", level + 1, level_count, title), String::new()];
+
+    lines.extend(draw_rings(&session.layouts, session.current_ring));
+    lines.push(String::new());
+
+    if session.is_complete() {
+        let perfect = session.solves.iter().filter(|solve| matches!(solve, SolveAttempt::Perfect(_))).count();
+        lines.push(format!("summoned! {} of {} rings perfect, {} misses", perfect, session.solves.len(), session.misses));
+        lines.push(String::new());
+        lines.push("enter: next level   ctrl-c: quit".to_string());
+    }
+    else {
+        let dead_end = if session.is_dead_end() { "   (no word starts like that)" } else { "" };
+        lines.push(format!("ring {}/{}  > {}{}", session.current_ring + 1, session.layouts.len(), session.built_word, dead_end));
+        lines.push(session.message.clone());
+        lines.push("type runes   backspace: undo   esc: reset   ctrl-c: quit".to_string());
+    }
+
+    queue!(stdout, Clear(ClearType::All), MoveTo(0, 0))?;
+    // raw mode doesn't return the carriage on a new line
+    write!(stdout, "{}", lines.join("\r\n"))?;
+    stdout.flush()
+}
@@ -0,0 +1,154 @@
+use puzzle_core::*;
+
+// One level being played in the terminal, going by the same rules as the game's systems
+pub struct Session<'a> {
+    pub puzzle: Puzzle,
+    pub layouts: Vec<RingLayout>,
+    pub current_ring: usize,
+    pub built_word: String,
+    pub solves: Vec<SolveAttempt>,
+    pub misses: u32,
+    pub message: String,
+    rules: SelectionRules,
+    word_list: &'a WordList,
+}
+
+impl<'a> Session<'a> {
+    pub fn new(puzzle: Puzzle, rules: SelectionRules, word_list: &'a WordList) -> Session<'a> {
+        let layouts = puzzle.rings.iter()
+            .map(|ring| ring.layout().expect("packs are validated before they are played"))
+            .collect();
+
+        Session {
+            puzzle,
+            layouts,
+            current_ring: 0,
+            built_word: String::new(),
+            solves: Vec::new(),
+            misses: 0,
+            message: String::new(),
+            rules,
+            word_list,
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.current_ring >= self.layouts.len()
+    }
+
+    pub fn is_dead_end(&self) -> bool {
+        !self.is_complete() && SolveAttempt::is_dead_end(&self.built_word, &self.puzzle.rings[self.current_ring].word, self.word_list)
+    }
+
+    // A typed letter picks the rune on the active ring, anything not on the ring is ignored like in the game
+    pub fn type_letter(&mut self, letter: char) {
+        if self.is_complete() {
+            return;
+        }
+
+        let letter = letter.to_ascii_uppercase();
+        let ring_size = self.layouts[self.current_ring].len();
+        if !self.layouts[self.current_ring].letters.contains(&letter) {
+            return;
+        }
+
+        if let Err(violation) = self.rules.check_tap(&self.built_word, letter, ring_size) {
+            self.message = match SolveAttempt::refused_tap(&self.built_word, letter, ring_size, violation) {
+                Some(SolveAttempt::Rejected { word, reason }) => {
+                    self.misses += 1;
+                    format!("rejected {}: {}", word, reason)
+                }
+                _ => format!("can't select {}: {}", letter, violation),
+            };
+            return;
+        }
+
+        self.built_word.push(letter);
+        self.check_word();
+    }
+
+    pub fn backspace(&mut self) {
+        if self.built_word.pop().is_some() {
+            self.check_word();
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.built_word.clear();
+        self.message.clear();
+    }
+
+    fn check_word(&mut self) {
+        if self.is_complete() {
+            return;
+        }
+
+        let target = &self.puzzle.rings[self.current_ring].word;
+        self.message.clear();
+
+        let Some(attempt) = SolveAttempt::check(&self.built_word, target, &self.rules, self.word_list) else {
+            return;
+        };
+
+        match &attempt {
+            SolveAttempt::Perfect(word) => self.message = format!("{} - perfect solve!", word),
+            SolveAttempt::Alternate(word) => self.message = format!("{} - alternate solve!", word),
+            SolveAttempt::Rejected { word, reason } => {
+                self.misses += 1;
+                self.message = format!("rejected {}: {}", word, reason);
+                return;
+            }
+        }
+
+        self.solves.push(attempt);
+        self.current_ring += 1;
+        self.built_word.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typing_every_ring_completes_the_puzzle() {
+        let word_list = WordList::from_text("mayhem threat hearth", &SelectionRules::default());
+        let mut session = Session::new(Puzzle::from_rings(0, &[("mayhem", 3, 1), ("threat", 3, 3)]), SelectionRules::default(), &word_list);
+
+        "mayhem".chars().for_each(|c| session.type_letter(c));
+        assert_eq!(session.current_ring, 1);
+        assert!(session.built_word.is_empty());
+
+        "hearth".chars().for_each(|c| session.type_letter(c));
+        assert!(session.is_complete());
+        assert_eq!(session.solves, vec![SolveAttempt::Perfect("MAYHEM".to_string()), SolveAttempt::Alternate("HEARTH".to_string())]);
+    }
+
+    #[test]
+    fn letters_off_the_ring_and_reused_runes_are_refused() {
+        let word_list = WordList::from_text("mayhem", &SelectionRules::default());
+        let mut session = Session::new(Puzzle::from_rings(0, &[("mayhem", 3, 1)]), SelectionRules::default(), &word_list);
+
+        "mazm".chars().for_each(|c| session.type_letter(c));
+
+        assert_eq!(session.built_word, "MA");
+        assert_eq!(session.misses, 0);
+    }
+
+    #[test]
+    fn wrong_words_count_as_misses_and_can_be_undone() {
+        let word_list = WordList::from_text("mayhem", &SelectionRules::default());
+        let mut session = Session::new(Puzzle::from_rings(0, &[("mayhem", 3, 1)]), SelectionRules::default(), &word_list);
+
+        "maehym".chars().for_each(|c| session.type_letter(c));
+        assert_eq!(session.misses, 1);
+        assert!(session.is_dead_end());
+
+        session.backspace();
+        assert_eq!(session.built_word, "MAEHY");
+
+        session.reset();
+        assert!(session.built_word.is_empty());
+        assert!(!session.is_dead_end());
+    }
+}
@@ -0,0 +1,100 @@
+use puzzle_core::*;
+
+pub struct RingAlternates {
+    pub level: usize,
+    pub ring: usize,
+    pub target: String,
+    pub alternates: Vec<String>,
+}
+
+// Every word that would complete this ring besides the intended one
+pub fn ring_alternates(ring: &Ring, word_list: &WordList, rules: &SelectionRules) -> Vec<String> {
+    // rings that can't be laid out are reported by validation instead
+    let Ok(layout) = ring.layout() else {
+        return Vec::new();
+    };
+
+    layout.solutions(word_list, rules).into_iter()
+        .filter(|word| !word.eq_ignore_ascii_case(&ring.word))
+        .collect()
+}
+
+pub fn ambiguity_report(pack: &PuzzlePack, word_list: &WordList) -> Vec<RingAlternates> {
+    let mut report = Vec::new();
+
+    for (level_index, level) in pack.levels.iter().enumerate() {
+        for (ring_index, ring) in level.rings.iter().enumerate() {
+            report.push(RingAlternates {
+                level: level_index,
+                ring: ring_index,
+                target: ring.word.clone(),
+                alternates: ring_alternates(ring, word_list, &pack.rules),
+            });
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzles::*;
+    use crate::worldlist::*;
+
+    #[test]
+    fn shipped_rings_use_every_interior_letter() {
+        for level in shipped_pack().levels {
+            for ring in level.rings {
+                let mut letters = ring.layout().unwrap().letters;
+                let mut expected: Vec<char> = ring.word[..ring.word.len() - 1].to_ascii_uppercase().chars().collect();
+                letters.sort();
+                expected.sort();
+
+                assert_eq!(letters, expected, "ring for {} lost letters", ring.word);
+            }
+        }
+    }
+
+    #[test]
+    fn shipped_targets_trace_a_closed_loop() {
+        for level in shipped_pack().levels {
+            for ring in level.rings {
+                let trace = ring.layout().unwrap().trace(&ring.word).expect("target should be on its ring");
+
+                assert_eq!(trace.first(), trace.last(), "{} does not close its loop", ring.word);
+            }
+        }
+    }
+
+    #[test]
+    fn shipped_targets_are_solutions() {
+        let word_list = shipped_word_list(&SelectionRules::default());
+
+        for level in shipped_pack().levels {
+            for ring in level.rings {
+                let solutions = ring.layout().unwrap().solutions(&word_list, &SelectionRules::default());
+
+                assert!(solutions.contains(&ring.word.to_ascii_uppercase()), "{} not found among {:?}", ring.word, solutions);
+            }
+        }
+    }
+
+    #[test]
+    fn alternates_leave_out_the_target() {
+        let ring = Ring { word: "threat".to_string(), step: 3, start: 3 };
+        let mut alternates = ring_alternates(&ring, &shipped_word_list(&SelectionRules::default()), &SelectionRules::default());
+        alternates.sort();
+
+        assert_eq!(alternates, vec!["HEARTH", "RATHER"]);
+    }
+
+    #[test]
+    fn reuse_rules_find_words_repeating_a_letter() {
+        let reuse = SelectionRules { rune_usage: RuneUsage::Reuse, ..Default::default() };
+        let ring = Ring { word: "ricer".to_string(), step: 1, start: 0 };
+
+        assert!(!ring_alternates(&ring, &shipped_word_list(&SelectionRules::default()), &SelectionRules::default()).contains(&"EERIE".to_string()));
+        assert!(ring_alternates(&ring, &shipped_word_list(&reuse), &reuse).contains(&"EERIE".to_string()));
+    }
+}
@@ -0,0 +1,281 @@
+use bevy::prelude::*;
+use puzzle_core::*;
+use std::collections::HashMap;
+use thiserror::Error;
+
+use crate::daily::*;
+use crate::puzzles::*;
+use crate::states::*;
+
+// Share codes pack a level into bits, append a checksum and spell the bytes out in URL-safe base64:
+//   version:3 demon:5 rings:4, then per ring length:5 step:5 start:5 and 5 bits per letter
+const CODE_VERSION: u32 = 1;
+const CODE_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+const VERSION_BITS: u32 = 3;
+const DEMON_BITS: u32 = 5;
+const RING_COUNT_BITS: u32 = 4;
+const RING_FIELD_BITS: u32 = 5;
+const LETTER_BITS: u32 = 5;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ShareCodeError {
+    #[error("{0} does not fit in a share code")]
+    TooLarge(String),
+    #[error("share code contains '{0}'")]
+    InvalidCharacter(char),
+    #[error("share code is incomplete")]
+    Truncated,
+    #[error("share code has been mistyped, the checksum doesn't match")]
+    ChecksumMismatch,
+    #[error("share code is from an unknown version {0}")]
+    UnsupportedVersion(u32),
+    #[error("share code holds an invalid level:{}", .0.iter().map(|issue| format!("\n  {}", issue)).collect::<String>())]
+    Invalid(Vec<PuzzleIssue>),
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    used: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u32) {
+        for bit in (0..bits).rev() {
+            let offset = self.used % 8;
+            if offset == 0 {
+                self.bytes.push(0);
+            }
+            let last = self.bytes.len() - 1;
+            self.bytes[last] |= (((value >> bit) & 1) as u8) << (7 - offset);
+            self.used += 1;
+        }
+    }
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    read: u32,
+}
+
+impl BitReader<'_> {
+    fn read(&mut self, bits: u32) -> Result<u32, ShareCodeError> {
+        let mut value = 0;
+        for _ in 0..bits {
+            let byte = self.bytes.get((self.read / 8) as usize).ok_or(ShareCodeError::Truncated)?;
+            value = (value << 1) | ((byte >> (7 - self.read % 8)) & 1) as u32;
+            self.read += 1;
+        }
+
+        Ok(value)
+    }
+}
+
+// Fletcher-16, enough to catch a mistyped or cut off code
+fn checksum(bytes: &[u8]) -> [u8; 2] {
+    let (mut low, mut high) = (0u16, 0u16);
+    for byte in bytes {
+        low = (low + *byte as u16) % 255;
+        high = (high + low) % 255;
+    }
+
+    [high as u8, low as u8]
+}
+
+fn to_base64(bytes: &[u8]) -> String {
+    let mut reader = BitReader { bytes, read: 0 };
+    let bits = bytes.len() as u32 * 8;
+    let mut code = String::new();
+
+    while reader.read < bits {
+        // the last character is padded out with zero bits
+        let chunk = (bits - reader.read).min(6);
+        let value = reader.read(chunk).unwrap() << (6 - chunk);
+        code.push(CODE_ALPHABET[value as usize] as char);
+    }
+
+    code
+}
+
+fn from_base64(code: &str) -> Result<Vec<u8>, ShareCodeError> {
+    let mut writer = BitWriter { bytes: Vec::new(), used: 0 };
+
+    for c in code.chars() {
+        let value = CODE_ALPHABET.iter()
+            .position(|symbol| *symbol as char == c)
+            .ok_or(ShareCodeError::InvalidCharacter(c))?;
+        writer.write(value as u32, 6);
+    }
+
+    // the padding bits of the last character spill into a partial byte, drop it
+    writer.bytes.truncate((writer.used / 8) as usize);
+    Ok(writer.bytes)
+}
+
+fn checked_field(value: usize, bits: u32, what: &str) -> Result<u32, ShareCodeError> {
+    if value < 1 << bits {
+        Ok(value as u32)
+    }
+    else {
+        Err(ShareCodeError::TooLarge(format!("{} {}", what, value)))
+    }
+}
+
+pub fn encode_level(level: &Puzzle) -> Result<String, ShareCodeError> {
+    let mut writer = BitWriter { bytes: Vec::new(), used: 0 };
+
+    writer.write(CODE_VERSION, VERSION_BITS);
+    writer.write(checked_field(level.demon, DEMON_BITS, "demon")?, DEMON_BITS);
+    writer.write(checked_field(level.rings.len(), RING_COUNT_BITS, "ring count")?, RING_COUNT_BITS);
+
+    for ring in level.rings.iter() {
+        writer.write(checked_field(ring.word.len(), RING_FIELD_BITS, "word length")?, RING_FIELD_BITS);
+        writer.write(checked_field(ring.step, RING_FIELD_BITS, "step")?, RING_FIELD_BITS);
+        writer.write(checked_field(ring.start, RING_FIELD_BITS, "start")?, RING_FIELD_BITS);
+
+        for c in ring.word.chars() {
+            if !c.is_ascii_alphabetic() {
+                return Err(ShareCodeError::TooLarge(format!("letter {}", c)));
+            }
+            writer.write((c.to_ascii_lowercase() as u8 - b'a') as u32, LETTER_BITS);
+        }
+    }
+
+    let sum = checksum(&writer.bytes);
+    writer.bytes.extend(sum);
+
+    Ok(to_base64(&writer.bytes))
+}
+
+pub fn decode_level(code: &str) -> Result<Puzzle, ShareCodeError> {
+    let mut bytes = from_base64(code.trim())?;
+    if bytes.len() < 3 {
+        return Err(ShareCodeError::Truncated);
+    }
+
+    let sum = bytes.split_off(bytes.len() - 2);
+    if sum != checksum(&bytes) {
+        return Err(ShareCodeError::ChecksumMismatch);
+    }
+
+    let mut reader = BitReader { bytes: &bytes, read: 0 };
+
+    let version = reader.read(VERSION_BITS)?;
+    if version != CODE_VERSION {
+        return Err(ShareCodeError::UnsupportedVersion(version));
+    }
+
+    let demon = reader.read(DEMON_BITS)? as usize;
+    let ring_count = reader.read(RING_COUNT_BITS)?;
+
+    let mut rings = Vec::new();
+    for _ in 0..ring_count {
+        let length = reader.read(RING_FIELD_BITS)?;
+        let step = reader.read(RING_FIELD_BITS)? as usize;
+        let start = reader.read(RING_FIELD_BITS)? as usize;
+
+        let mut word = String::new();
+        for _ in 0..length {
+            let letter = reader.read(LETTER_BITS)?;
+            if letter >= 26 {
+                return Err(ShareCodeError::InvalidCharacter((b'a' + letter as u8) as char));
+            }
+            word.push((b'a' + letter as u8) as char);
+        }
+
+        rings.push(Ring { word, step, start });
+    }
+
+    let level = Puzzle {
+        demon,
+        rings,
+        title: None,
+        metadata: HashMap::new(),
+    };
+
+    let issues = validate_level(0, &level, DEMON_SPRITES.len());
+    if !issues.is_empty() {
+        return Err(ShareCodeError::Invalid(issues));
+    }
+
+    Ok(level)
+}
+
+// A code to open straight away, `--puzzle <code>` natively or `?puzzle=<code>` in the page URL
+#[cfg(not(target_arch = "wasm32"))]
+pub fn launch_code(args: &[String]) -> Option<String> {
+    args.iter()
+        .position(|arg| arg == "--puzzle")
+        .and_then(|index| args.get(index + 1))
+        .cloned()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn launch_code(_args: &[String]) -> Option<String> {
+    let search = web_sys::window()?.location().search().ok()?;
+
+    search.trim_start_matches('?')
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("puzzle="))
+        .map(str::to_string)
+}
+
+// A level from a share code given at launch, started once the menus are up
+#[derive(Resource)]
+pub struct LaunchPuzzle {
+    pub shared: Option<(String, Puzzle)>,
+}
+
+pub fn open_launch_puzzle(
+    mut launch: ResMut<LaunchPuzzle>,
+    mut mode: ResMut<PlayMode>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if let Some((code, level)) = launch.shared.take() {
+        *mode = PlayMode::Shared { code, level };
+        next_state.set(GameState::Playing);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_round_trip_levels() {
+        let original = Puzzle::from_rings(3, &[("mayhem", 3, 1), ("threat", 2, 4), ("lethal", 1, 0)]);
+        let code = encode_level(&original).unwrap();
+
+        assert!(code.bytes().all(|c| CODE_ALPHABET.contains(&c)));
+        assert_eq!(decode_level(&code).unwrap(), original);
+    }
+
+    #[test]
+    fn every_shipped_level_has_a_code() {
+        let pack = shipped_pack();
+
+        for original in pack.levels.iter() {
+            let code = encode_level(original).unwrap();
+            assert_eq!(decode_level(&code).unwrap().rings, original.rings);
+        }
+    }
+
+    #[test]
+    fn mistyped_codes_are_caught() {
+        let code = encode_level(&Puzzle::from_rings(0, &[("mayhem", 3, 1)])).unwrap();
+
+        let mut mistyped: Vec<char> = code.chars().collect();
+        mistyped[2] = if mistyped[2] == 'A' { 'B' } else { 'A' };
+        let mistyped: String = mistyped.into_iter().collect();
+
+        assert_eq!(decode_level(&mistyped), Err(ShareCodeError::ChecksumMismatch));
+        assert_eq!(decode_level(&code[..code.len() - 4]), Err(ShareCodeError::ChecksumMismatch));
+        assert_eq!(decode_level("AB"), Err(ShareCodeError::Truncated));
+        assert_eq!(decode_level("not a code"), Err(ShareCodeError::InvalidCharacter(' ')));
+    }
+
+    #[test]
+    fn oversized_levels_are_refused() {
+        assert!(matches!(encode_level(&Puzzle::from_rings(40, &[("mayhem", 3, 1)])), Err(ShareCodeError::TooLarge(_))));
+    }
+}
@@ -0,0 +1,126 @@
+use bevy::prelude::*;
+use puzzle_core::*;
+use serde::{Deserialize, Serialize};
+
+use crate::generator::*;
+use crate::puzzles::*;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// Days since the unix epoch in UTC, so every player rolls over to the next puzzle together
+#[cfg(not(target_arch = "wasm32"))]
+pub fn today() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() / SECONDS_PER_DAY)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn today() -> u64 {
+    (js_sys::Date::now() / 1000.) as u64 / SECONDS_PER_DAY
+}
+
+// The day number doubles as the generator seed, `generate --seed <day> --levels 1` prints the same level
+pub fn daily_level(day: u64) -> Puzzle {
+    let mut level = generate_level(day, &GeneratorSettings::default(), &WordBuckets::default())
+        .expect("the default settings fit the shipped word lists");
+    level.title = Some(format!("Daily {}", day));
+
+    level
+}
+
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub enum PlayMode {
+    #[default]
+    Campaign,
+    Daily { day: u64, level: Puzzle },
+    // a level opened from a share code
+    Shared { code: String, level: Puzzle },
+}
+
+impl PlayMode {
+    pub fn daily(day: u64) -> PlayMode {
+        PlayMode::Daily { day, level: daily_level(day) }
+    }
+
+    pub fn level<'a>(&'a self, puzzles_list: &'a PuzzlesList) -> &'a Puzzle {
+        match self {
+            PlayMode::Campaign => &puzzles_list.list[puzzles_list.current],
+            PlayMode::Daily { level, .. } | PlayMode::Shared { level, .. } => level,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct DailyProgress {
+    pub last_played: Option<u64>,
+    pub last_score: Option<u32>,
+    pub streak: u32,
+    pub best_streak: u32,
+}
+
+impl DailyProgress {
+    pub fn played_on(&self, day: u64) -> bool {
+        self.last_played == Some(day)
+    }
+
+    // A streak survives until a whole day goes by without playing
+    pub fn current_streak(&self, today: u64) -> u32 {
+        match self.last_played {
+            Some(day) if day + 1 >= today => self.streak,
+            _ => 0,
+        }
+    }
+
+    pub fn record(&mut self, day: u64, score: u32) {
+        if self.played_on(day) {
+            return;
+        }
+
+        self.streak = if self.last_played.is_some_and(|last| last + 1 == day) { self.streak + 1 } else { 1 };
+        self.best_streak = self.best_streak.max(self.streak);
+        self.last_played = Some(day);
+        self.last_score = Some(score);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn everyone_gets_the_same_daily_level() {
+        let level = daily_level(20_000);
+
+        assert_eq!(level, daily_level(20_000));
+        assert_ne!(level.rings, daily_level(20_001).rings);
+        assert!(validate_level(0, &level, DEMON_SPRITES.len()).is_empty());
+    }
+
+    #[test]
+    fn streaks_grow_on_consecutive_days() {
+        let mut progress = DailyProgress::default();
+
+        progress.record(100, 300);
+        progress.record(101, 250);
+        progress.record(101, 900);
+
+        assert_eq!(progress.streak, 2);
+        assert_eq!(progress.last_score, Some(250));
+        assert!(progress.played_on(101));
+        assert_eq!(progress.current_streak(102), 2);
+    }
+
+    #[test]
+    fn missing_a_day_breaks_the_streak() {
+        let mut progress = DailyProgress::default();
+
+        progress.record(100, 300);
+        progress.record(101, 300);
+        assert_eq!(progress.current_streak(103), 0);
+
+        progress.record(103, 300);
+        assert_eq!(progress.streak, 1);
+        assert_eq!(progress.best_streak, 2);
+    }
+}
@@ -0,0 +1,212 @@
+use puzzle_core::*;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::puzzles::*;
+
+// Word lists split by length, as written out by the word_filter tool
+pub struct WordBuckets {
+    pub buckets: BTreeMap<usize, Vec<&'static str>>,
+}
+
+impl Default for WordBuckets {
+    fn default() -> Self {
+        let files = [
+            include_str!("../word_filter/5filtered.txt"),
+            include_str!("../word_filter/6filtered.txt"),
+            include_str!("../word_filter/7filtered.txt"),
+            include_str!("../word_filter/8filtered.txt"),
+            include_str!("../word_filter/9filtered.txt"),
+            include_str!("../word_filter/10filtered.txt"),
+            include_str!("../word_filter/11filtered.txt"),
+            include_str!("../word_filter/12filtered.txt"),
+        ];
+
+        let mut buckets: BTreeMap<usize, Vec<&'static str>> = BTreeMap::new();
+
+        for contents in files {
+            for word in contents.split_whitespace() {
+                // skip acronyms and proper nouns, the rings are all lower case
+                if !word.bytes().all(|c| c.is_ascii_lowercase()) {
+                    continue;
+                }
+
+                let bucket = buckets.entry(word.len()).or_default();
+                if !bucket.contains(&word) {
+                    bucket.push(word);
+                }
+            }
+        }
+
+        WordBuckets { buckets }
+    }
+}
+
+pub struct GeneratorSettings {
+    pub rings: usize,
+    pub min_length: usize,
+    pub max_length: usize,
+    pub demon_count: usize,
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        GeneratorSettings {
+            rings: 2,
+            min_length: 6,
+            max_length: 8,
+            demon_count: DEMON_SPRITES.len(),
+        }
+    }
+}
+
+impl GeneratorSettings {
+    // Settings that can't make a playable level whatever the word lists hold
+    pub fn check(&self) -> Result<(), String> {
+        if self.rings == 0 {
+            return Err("a level needs at least one ring".to_string());
+        }
+        if self.min_length > self.max_length {
+            return Err(format!("the shortest word ({} letters) is longer than the longest ({} letters)", self.min_length, self.max_length));
+        }
+        if self.demon_count == 0 {
+            return Err("there are no demons to summon".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+fn choose_step(rng: &mut ChaCha8Rng, length: usize) -> usize {
+    // step 1 lays the word out in order, only fall back to it when nothing else scatters the letters
+    let steps: Vec<usize> = (2..length).filter(|step| gcd(*step, length) == 1).collect();
+
+    steps.choose(rng).copied().unwrap_or(1)
+}
+
+// Rings grow outwards, so every ring needs a word length of its own
+pub fn generate_level(seed: u64, settings: &GeneratorSettings, words: &WordBuckets) -> Result<Puzzle, String> {
+    settings.check()?;
+
+    // StdRng may change between rand releases, a daily level has to come out the same for everyone
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    let lengths: Vec<usize> = words.buckets.iter()
+        .filter(|(length, bucket)| **length > MIN_RING_RUNES && **length >= settings.min_length && **length <= settings.max_length && !bucket.is_empty())
+        .map(|(length, _)| *length)
+        .collect();
+    if lengths.len() < settings.rings {
+        return Err(format!(
+            "{} rings need {} different word lengths but there are only {} between {} and {} letters",
+            settings.rings, settings.rings, lengths.len(), settings.min_length, settings.max_length
+        ));
+    }
+
+    let mut ring_lengths: Vec<usize> = lengths.choose_multiple(&mut rng, settings.rings).copied().collect();
+    ring_lengths.sort();
+
+    let mut rings: Vec<Ring> = Vec::new();
+
+    for length in ring_lengths {
+        let word = words.buckets[&length].choose(&mut rng).expect("only lengths with words are chosen");
+
+        let ring_length = length - 1;
+        let ring = Ring {
+            word: word.to_string(),
+            step: choose_step(&mut rng, ring_length),
+            start: rng.gen_range(0..ring_length),
+        };
+
+        debug_assert!(validate_ring(&ring).is_empty(), "generated an invalid ring {:?}", ring);
+
+        rings.push(ring);
+    }
+
+    let mut metadata = HashMap::new();
+    metadata.insert("seed".to_string(), seed.to_string());
+
+    Ok(Puzzle {
+        demon: rng.gen_range(0..settings.demon_count),
+        rings,
+        title: None,
+        metadata,
+    })
+}
+
+pub fn generate_pack(seed: u64, levels: usize, settings: &GeneratorSettings, words: &WordBuckets) -> Result<PuzzlePack, String> {
+    Ok(PuzzlePack {
+        rules: SelectionRules::default(),
+        levels: (0..levels as u64)
+            .map(|i| generate_level(seed.wrapping_add(i), settings, words))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buckets(words: &[&'static str]) -> WordBuckets {
+        let mut buckets: BTreeMap<usize, Vec<&'static str>> = BTreeMap::new();
+        for word in words {
+            buckets.entry(word.len()).or_default().push(word);
+        }
+
+        WordBuckets { buckets }
+    }
+
+    #[test]
+    fn generated_levels_are_valid() {
+        let words = WordBuckets::default();
+        let settings = GeneratorSettings { rings: 4, min_length: 5, max_length: 12, ..Default::default() };
+
+        for seed in 0..50 {
+            let level = generate_level(seed, &settings, &words).unwrap();
+
+            assert_eq!(level.rings.len(), 4);
+            assert_eq!(validate_level(0, &level, settings.demon_count), vec![]);
+            assert!(level.rings.windows(2).all(|pair| pair[0].word.len() < pair[1].word.len()), "rings don't grow outwards in {:?}", level);
+        }
+    }
+
+    #[test]
+    fn the_seed_decides_the_level() {
+        let words = buckets(&["lethal", "mayhem", "threat", "rocker", "shrinks"]);
+        let settings = GeneratorSettings::default();
+
+        assert_eq!(generate_level(7, &settings, &words), generate_level(7, &settings, &words));
+        assert_eq!(generate_level(7, &settings, &words).unwrap().metadata["seed"], "7");
+    }
+
+    #[test]
+    fn seeds_make_the_same_level_on_every_build() {
+        // daily levels are shared by day number, so this must only change along with the word lists
+        let level = generate_level(20000, &GeneratorSettings::default(), &WordBuckets::default()).unwrap();
+
+        assert_eq!(level.demon, 3);
+        assert_eq!(level.rings, Puzzle::from_rings(3, &[("sibyls", 2, 4), ("nitrogen", 4, 1)]).rings);
+    }
+
+    #[test]
+    fn settings_that_cant_make_a_level_are_refused() {
+        let words = buckets(&["lethal", "mayhem", "shrinks"]);
+
+        assert!(generate_level(0, &GeneratorSettings { rings: 0, ..Default::default() }, &words).is_err());
+        assert!(generate_level(0, &GeneratorSettings { min_length: 30, max_length: 20, ..Default::default() }, &words).is_err());
+        assert!(generate_level(0, &GeneratorSettings { demon_count: 0, ..Default::default() }, &words).is_err());
+        // no words that long at all
+        assert!(generate_level(0, &GeneratorSettings { min_length: 20, max_length: 30, ..Default::default() }, &words).is_err());
+        // only two lengths for three rings
+        assert!(generate_level(0, &GeneratorSettings { rings: 3, ..Default::default() }, &words).is_err());
+        assert!(generate_level(0, &GeneratorSettings { rings: 2, ..Default::default() }, &words).is_ok());
+    }
+
+    #[test]
+    fn packs_stop_at_the_first_bad_level() {
+        let words = buckets(&["lethal"]);
+
+        assert_eq!(generate_pack(0, 3, &GeneratorSettings { rings: 1, ..Default::default() }, &words).unwrap().levels.len(), 3);
+        assert!(generate_pack(0, 3, &GeneratorSettings::default(), &words).is_err());
+    }
+}
@@ -1,14 +1,52 @@
-use std::f32::consts::PI;
-use bevy::{asset::AssetMetaCheck, prelude::*};
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
+use std::collections::HashMap;
+use bevy::{asset::{AssetLoadFailedEvent, AssetMetaCheck}, input::InputSystem, prelude::*};
 use bevy_prototype_lyon::prelude::*;
+use puzzle_core::*;
 
 use crate::worldlist::*;
 use crate::squashes::*;
+use crate::puzzles::*;
+use crate::states::*;
+use crate::save::*;
+use crate::scoring::*;
+use crate::daily::*;
+use crate::share::*;
+use crate::codes::*;
 
 mod worldlist;
 mod squashes;
+mod puzzles;
+mod states;
+mod save;
+mod scoring;
+mod daily;
+mod share;
+mod generator;
+mod ambiguity;
+mod tools;
+mod codes;
+#[cfg(test)]
+mod simulation;
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(exit_code) = tools::run_tool(&args) {
+        std::process::exit(exit_code);
+    }
+
+    let save_store = SaveStore { storage: default_storage() };
+    let save_data = save_store.load();
+
+    let shared = launch_code(&args).and_then(|code| match decode_level(&code) {
+        Ok(level) => Some((code, level)),
+        Err(err) => {
+            println!("{}, starting at the menu", err);
+            None
+        }
+    });
+
     App::new()
         .insert_resource(ClearColor(Color::BLACK))
         .insert_resource(AssetMetaCheck::Never)
@@ -25,56 +63,92 @@ fn main() {
                 ..default()
             }).set(ImagePlugin::default_nearest()))
         .add_plugins(ShapePlugin)
-        .insert_resource(WordList::default())
-        .insert_resource(WordSelection {
-            built_word: String::new(),
-            positions: Vec::new(),
-            changed_this_frame: true,
-            current_layer: 0,
-            current_layer_start_time: 0.,
-            target_word: String::new(),
-            complete_solution: Vec::new(),
-        })
-        .insert_resource(RuneTextStyles::default())
-        .insert_resource(DemonArts::default())
-        .insert_resource(MousePosition {
-            pos: None,
-            just_clicked: false,
-        })
-        .insert_resource(PuzzlesList {
-            list: vec![
-                (0, vec![("mayhem", 3, 1)]),
-                (0, vec![("entice", 4, 4)]),
-                (2, vec![("grasping", 3, 4)]),
-                (3, vec![("neuron", 4, 2), ("scraps", 2, 3)]),
-                (0, vec![("lethal", 3, 2), ("rocker", 1, 3)]),
-                (2, vec![("tyrant", 3, 5), ("turncoat", 2, 3)]),
-                (0, vec![("expire", 2, 2), ("lawful", 4, 3), ("gaming", 3, 3)]),
-                (3, vec![("threat", 3, 3), ("divulged", 1, 3)]),
-                (0, vec![("dashed", 3, 0), ("dunked", 1, 3), ("dumped", 3, 3)]),
-                (0, vec![("medium", 1, 4), ("eulogize", 4, 1)]),
-                (3, vec![("edible", 4, 3), ("snacks", 2, 3), ("spoils", 3, 1)]),
-                (1, vec![("damped", 3, 1), ("exorcise", 1, 2)]),
-                (0, vec![("cosmic", 2, 1), ("sundries", 3, 6)]),
-                (2, vec![("cleric", 2, 2), ("shirks", 4, 3), ("damned", 3, 3)]),
-                (0, vec![("dismayed", 4, 6), ("catholic", 1, 2)]),
-                (0, vec![("thrust", 4, 2), ("rapier", 1, 3), ("withdrew", 4, 3)]),
-                (2, vec![("teapot", 1, 2), ("subordinates", 5, 3)]),
-                (0, vec![("cosmetic", 2, 6), ("ghosting", 4, 3)]),
-                (3, vec![("expose", 2, 0), ("gaping", 4, 1), ("shreds", 1, 3)]),
-                (4, vec![("gyrating", 2, 5), ("spaceflights", 8, 7)]),
-            ],
-            current: 0,
-        })
-        .add_event::<WordCompleteEvent>()
-        .add_event::<PuzzleCompleteEvent>()
-        .add_systems(Startup, (load_fonts, load_demons, spawn_camera, spawn_edit_buttons, spawn_circle).chain())
-        .add_systems(Update, (update_active_ring, update_mouse_position, select_letters, handle_backspace, handle_reset, handle_next_level, check_complete, spawn_next_level).chain())
-        .add_systems(Update, (draw_selection, update_word_display, animate_demon, spin_rings))
-        .add_systems(Update, squish_effects)
+        .add_plugins(GameplayPlugin)
+        .insert_resource(save_store)
+        .insert_resource(save_data)
+        .insert_resource(LaunchPuzzle { shared })
+        .init_asset::<PuzzlePack>()
+        .init_asset_loader::<PuzzlePackLoader>()
+        .add_systems(Startup, (load_fonts, load_demons, spawn_camera, load_puzzle_pack).chain().before(spawn_edit_buttons))
+        .add_systems(PreUpdate, init_puzzles_list.run_if(not(resource_exists::<PuzzlesList>)))
+        .add_systems(PreUpdate, (update_mouse_position, read_typed_letters).after(InputSystem))
+        .add_systems(Update, reload_puzzle_pack.run_if(resource_exists::<PuzzlesList>))
         .run();
 }
 
+// The puzzles themselves: resources, states and the systems playing them. Input comes in through
+// MousePosition and TypedLetters rather than the window, so this also runs headless under MinimalPlugins
+struct GameplayPlugin;
+
+impl Plugin for GameplayPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(WordSelection {
+                built_word: String::new(),
+                positions: Vec::new(),
+                changed_this_frame: true,
+                current_layer: 0,
+                current_layer_start_time: 0.,
+                paused_at: None,
+                target_word: String::new(),
+                complete_solution: Vec::new(),
+                misses: Vec::new(),
+                hints: Vec::new(),
+                solves: Vec::new(),
+                tracing: false,
+                dead_end: false,
+            })
+            .init_resource::<SaveData>()
+            .insert_resource(LevelScore::default())
+            .insert_resource(PlayMode::default())
+            .insert_resource(LaunchPuzzle { shared: None })
+            .insert_resource(SelectionRules::default())
+            .insert_resource(RuneTextStyles::default())
+            .insert_resource(DemonArts::default())
+            .insert_resource(MousePosition {
+                pos: None,
+                just_clicked: false,
+                pressed: false,
+                just_released: false,
+            })
+            .insert_resource(TypedLetters::default())
+            .add_event::<WordCompleteEvent>()
+            .add_event::<WordRejectedEvent>()
+            .add_event::<PuzzleCompleteEvent>()
+            .init_state::<GameState>()
+            .add_systems(Startup, spawn_edit_buttons)
+            .add_systems(PreUpdate, load_word_list.run_if(resource_changed::<SelectionRules>))
+            .add_systems(OnEnter(GameState::MainMenu), (despawn_level, spawn_main_menu))
+            .add_systems(OnEnter(GameState::LevelSelect), (despawn_level, spawn_level_select))
+            .add_systems(OnEnter(GameState::Credits), (despawn_level, spawn_credits))
+            .add_systems(OnEnter(GameState::Paused), (spawn_pause_menu, stop_ring_clock))
+            .add_systems(OnEnter(GameState::Playing), (show_edit_buttons, enter_level, remember_current_level))
+            .add_systems(OnExit(GameState::Playing), hide_edit_buttons)
+            .add_systems(OnEnter(GameState::DemonReveal), (show_edit_buttons, spawn_share_button, (show_score, record_progress).chain()))
+            .add_systems(OnExit(GameState::DemonReveal), (hide_edit_buttons, despawn_level))
+            .add_systems(OnExit(GameState::MainMenu), despawn_menu)
+            .add_systems(OnExit(GameState::LevelSelect), despawn_menu)
+            .add_systems(OnExit(GameState::Credits), despawn_menu)
+            .add_systems(OnExit(GameState::Paused), (despawn_menu, restart_ring_clock))
+            .add_systems(Update, (
+                update_active_ring.run_if(in_state(GameState::Playing).or_else(in_state(GameState::DemonReveal))),
+                (select_letters, handle_backspace, handle_reset, handle_hint, clear_dead_end, check_complete, score_solves, react_to_rejection, show_dead_end, pause_game).chain().run_if(in_state(GameState::Playing)),
+                (handle_next_level, share_result, spawn_next_level).chain().run_if(in_state(GameState::DemonReveal)),
+                (open_launch_puzzle, handle_menu_buttons, leave_menu).chain().run_if(not(in_state(GameState::Playing)).and_then(not(in_state(GameState::DemonReveal)))),
+            ).chain().run_if(resource_exists::<PuzzlesList>))
+            .add_systems(Update, (draw_selection, draw_pointer, update_word_display, show_hints).run_if(in_state(GameState::Playing)))
+            .add_systems(Update, (animate_demon, spin_rings).run_if(in_state(GameState::DemonReveal)))
+            .add_systems(Update, (squish_effects, shake_effects));
+    }
+}
+
+const WORD_LINE_COLOR: Color = Color::rgb(1.0, 0.3, 0.3);
+const DEAD_END_LINE_COLOR: Color = Color::rgb(0.35, 0.35, 0.35);
+const HINT_COLOR: Color = Color::rgb(1.0, 0.8, 0.2);
+const MAX_HINT_LEVEL: u32 = 3;
+const TRACE_RADIUS_SCALE: f32 = 0.6;
+const HOVER_RING_RADIUS: f32 = 30.;
+
 #[derive(Component, Default)]
 struct RingLayer {
     layer: u32,
@@ -83,12 +157,6 @@ struct RingLayer {
 #[derive(Component, Default)]
 struct LevelObject {}
 
-#[derive(Resource)]
-struct PuzzlesList {
-    list: Vec<(usize, Vec<(&'static str, usize, usize)>)>,
-    current: usize,
-}
-
 #[derive(Resource)]
 struct WordSelection {
     built_word: String,
@@ -96,8 +164,26 @@ struct WordSelection {
     changed_this_frame: bool,
     current_layer: u32,
     current_layer_start_time: f32,
+    // when the pause menu opened, the time spent in it doesn't count towards the ring
+    paused_at: Option<f32>,
     target_word: String,
     complete_solution: Vec<String>,
+    // rejected words per ring of the current level
+    misses: Vec<u32>,
+    // how far the hints have escalated per ring of the current level
+    hints: Vec<u32>,
+    // rings solved so far in the current level
+    solves: Vec<RingSolve>,
+    // a press on a rune is being dragged across the ring
+    tracing: bool,
+    // no valid word starts with built_word
+    dead_end: bool,
+}
+
+struct RingSolve {
+    word: String,
+    perfect: bool,
+    seconds: f32,
 }
 
 #[derive(Resource, Default)]
@@ -112,10 +198,19 @@ struct DemonArts {
     sprites: Vec<Handle<Image>>,
 }
 
+// Letters typed on the keyboard this frame
+#[derive(Resource, Default)]
+struct TypedLetters {
+    letters: Vec<char>,
+}
+
 #[derive(Resource)]
 struct MousePosition {
     pos: Option<Vec2>,
     just_clicked: bool,
+    // held down, by the mouse button or a finger
+    pressed: bool,
+    just_released: bool,
 }
 
 #[derive(Component)]
@@ -140,16 +235,39 @@ struct WordLine {
     layer: u32,
 }
 
+#[derive(Component)]
+struct PointerLine {}
+
+#[derive(Component)]
+struct HoverRing {}
+
+#[derive(Component)]
+struct HintLine {
+    layer: u32,
+}
+
+#[derive(Component)]
+struct HintDisplay {}
+
 #[derive(Event)]
 struct WordCompleteEvent {
     now_on_layer: u32,
 }
 
+#[derive(Event)]
+struct WordRejectedEvent {
+    word: String,
+    reason: RejectReason,
+}
+
 #[derive(Event)]
 struct PuzzleCompleteEvent {
     
 }
 
+#[derive(Component)]
+struct EditButtons {}
+
 #[derive(Component, Default)]
 struct BackspaceButton {
     active: bool
@@ -165,6 +283,11 @@ struct NextLevelButton {
     active: bool
 }
 
+#[derive(Component, Default)]
+struct HintButton {
+    active: bool
+}
+
 #[derive(Component)]
 struct DemonFace {
     base_scale: f32,
@@ -189,6 +312,15 @@ fn spawn_edit_buttons(
     mut commands: Commands,
     rune_fonts: ResMut<RuneTextStyles>,
 ) {
+    // the buttons only show while a level is on screen, see show_edit_buttons
+    let root = commands.spawn((
+        SpatialBundle {
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        EditButtons {},
+    )).id();
+
     commands.spawn((
         Text2dBundle {
             text: Text::from_section("<", rune_fonts.display.clone()),
@@ -199,7 +331,7 @@ fn spawn_edit_buttons(
             active: true,
         },
         SquishEffect::new(Vec3::ONE, Vec3::splat(2.), 0.01, 0., 0.25),
-    ));
+    )).set_parent(root);
 
     commands.spawn((
         Text2dBundle {
@@ -211,7 +343,19 @@ fn spawn_edit_buttons(
             active: true,
         },
         SquishEffect::new(Vec3::ONE, Vec3::splat(2.), 0.01, 0., 0.25),
-    ));
+    )).set_parent(root);
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section("?", rune_fonts.display.clone()),
+            transform: Transform::from_translation(Vec3::new(150., -350., 0.)),
+            ..default()
+        },
+        HintButton {
+            active: true,
+        },
+        SquishEffect::new(Vec3::ONE, Vec3::splat(2.), 0.01, 0., 0.25),
+    )).set_parent(root);
 
     commands.spawn((
         Text2dBundle {
@@ -223,18 +367,28 @@ fn spawn_edit_buttons(
         NextLevelButton {
             active: false,
         },
-    ));
+    )).set_parent(root);
+}
+
+fn show_edit_buttons(
+    mut root_query: Query<&mut Visibility, With<EditButtons>>,
+) {
+    *root_query.single_mut() = Visibility::Inherited;
+}
+
+fn hide_edit_buttons(
+    mut root_query: Query<&mut Visibility, With<EditButtons>>,
+) {
+    *root_query.single_mut() = Visibility::Hidden;
 }
 
 fn load_demons(
     mut demon_art: ResMut<DemonArts>,
     asset_server: Res<AssetServer>,
 ) {
-    demon_art.sprites.push(asset_server.load("sprites/demon01.png"));
-    demon_art.sprites.push(asset_server.load("sprites/demon02.png"));
-    demon_art.sprites.push(asset_server.load("sprites/demon03.png"));
-    demon_art.sprites.push(asset_server.load("sprites/demon04.png"));
-    demon_art.sprites.push(asset_server.load("sprites/demon05.png"));
+    for path in DEMON_SPRITES {
+        demon_art.sprites.push(asset_server.load(path));
+    }
 
     println!("{} demonic arts loaded", demon_art.sprites.len());
 }
@@ -264,58 +418,178 @@ fn load_fonts(
     }
 }
 
-fn spawn_circle(
+fn init_puzzles_list(
+    mut commands: Commands,
+    pack_handle: Res<PuzzlePackHandle>,
+    packs: Res<Assets<PuzzlePack>>,
+    save_data: Res<SaveData>,
+) {
+    let Some(pack) = packs.get(&pack_handle.0) else {
+        return;
+    };
+
+    commands.insert_resource(pack.rules);
+    commands.insert_resource(PuzzlesList {
+        list: pack.levels.clone(),
+        current: save_data.current_level.min(pack.levels.len() - 1),
+    });
+}
+
+fn enter_level(
     mut commands: Commands,
     font_settings: Res<RuneTextStyles>,
     mut solution: ResMut<WordSelection>,
     demons: Res<DemonArts>,
     time: Res<Time>,
     puzzles_list: Res<PuzzlesList>,
+    mode: Res<PlayMode>,
+    mut world_completion_writer: EventWriter<WordCompleteEvent>,
+    level_entities: Query<Entity, With<LevelObject>>,
 ) {
-    spawn_level(puzzles_list.current, &puzzles_list,&mut commands,  &font_settings, &mut solution, &demons, &time);
+    // coming back from the pause menu, the level is still there
+    if !level_entities.is_empty() {
+        return;
+    }
+
+    spawn_level(mode.level(&puzzles_list), &mut commands, &font_settings, &mut solution, &demons, &time);
+
+    world_completion_writer.send(WordCompleteEvent{now_on_layer: 0});
+}
+
+fn stop_ring_clock(
+    mut selection: ResMut<WordSelection>,
+    time: Res<Time>,
+) {
+    selection.paused_at = Some(time.elapsed_seconds());
+}
+
+fn restart_ring_clock(
+    mut selection: ResMut<WordSelection>,
+    time: Res<Time>,
+) {
+    if let Some(paused_at) = selection.paused_at.take() {
+        selection.current_layer_start_time += time.elapsed_seconds() - paused_at;
+    }
+}
+
+fn despawn_level(
+    mut commands: Commands,
+    clear_entities: Query<Entity, With<LevelObject>>,
+) {
+    for entity in clear_entities.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
 }
 
 fn spawn_next_level(
+    mut puzzles_list: ResMut<PuzzlesList>,
+    mode: Res<PlayMode>,
+    mut puzzle_completion_reader: EventReader<PuzzleCompleteEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if puzzle_completion_reader.read().last().is_some() {
+        // daily and shared levels stand alone, head back to the menu
+        if *mode != PlayMode::Campaign {
+            next_state.set(GameState::MainMenu);
+            return;
+        }
+
+        puzzles_list.current = (puzzles_list.current + 1) % puzzles_list.list.len();
+
+        // leaving the reveal clears the old level and entering play spawns the new one
+        next_state.set(GameState::Playing);
+    }
+}
+
+fn reload_puzzle_pack(
     mut commands: Commands,
     font_settings: Res<RuneTextStyles>,
     mut solution: ResMut<WordSelection>,
     demons: Res<DemonArts>,
     time: Res<Time>,
     mut puzzles_list: ResMut<PuzzlesList>,
-    mut puzzle_completion_reader: EventReader<PuzzleCompleteEvent>,
+    mode: Res<PlayMode>,
+    pack_handle: Res<PuzzlePackHandle>,
+    packs: Res<Assets<PuzzlePack>>,
+    mut pack_events: EventReader<AssetEvent<PuzzlePack>>,
+    mut failed_loads: EventReader<AssetLoadFailedEvent<PuzzlePack>>,
     mut world_completion_writer: EventWriter<WordCompleteEvent>,
     clear_entities: Query<Entity, With<LevelObject>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
 ) {
-    for _event in puzzle_completion_reader.read() {
+    // a pack that doesn't parse or validate never replaces the loaded one
+    for failed in failed_loads.read() {
+        println!("could not load {}, keeping the current puzzles: {}", failed.path, failed.error);
+    }
+
+    for event in pack_events.read() {
+        if !event.is_modified(&pack_handle.0) {
+            continue;
+        }
+
+        let Some(pack) = packs.get(&pack_handle.0) else {
+            continue;
+        };
+
+        puzzles_list.list = pack.levels.clone();
+        commands.insert_resource(pack.rules);
+        puzzles_list.current = puzzles_list.current.min(puzzles_list.list.len() - 1);
+
+        if clear_entities.is_empty() {
+            // in the menus, the new levels get picked up next time one starts
+            continue;
+        }
+
+        if *mode != PlayMode::Campaign {
+            // daily and shared levels don't come from the pack, leave them be
+            continue;
+        }
+
+        println!("puzzle pack reloaded, respawning level {}", puzzles_list.current);
+
+        if *state.get() == GameState::DemonReveal {
+            next_state.set(GameState::Playing);
+            continue;
+        }
+
+        if *state.get() == GameState::Paused {
+            // resuming finds no level and spawns the new one
+            for entity in clear_entities.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+            continue;
+        }
+
         for entity in clear_entities.iter() {
             commands.entity(entity).despawn_recursive();
         }
-        
-        puzzles_list.current = (puzzles_list.current + 1) % puzzles_list.list.len();
 
-        spawn_level(puzzles_list.current, &puzzles_list,&mut commands,  &font_settings, &mut solution, &demons, &time);
+        spawn_level(&puzzles_list.list[puzzles_list.current], &mut commands, &font_settings, &mut solution, &demons, &time);
 
         world_completion_writer.send(WordCompleteEvent{now_on_layer: 0});
     }
 }
 
 fn spawn_level(
-    index: usize,
-    puzzles_list: &PuzzlesList,
+    level: &Puzzle,
     commands: &mut Commands,
     font_settings: &RuneTextStyles,
     solution: &mut WordSelection,
     demons: &DemonArts,
     time: &Time,
 ) {
-    let (demon_choice, rings) = &puzzles_list.list[index];
 
     solution.complete_solution.clear();
+    solution.misses.clear();
+    solution.hints.clear();
+    solution.solves.clear();
 
-    let demon = &demons.sprites[*demon_choice];
+    // no art loaded when running headless
+    let demon = demons.sprites.get(level.demon).cloned().unwrap_or_default();
     spawn_puzzle(commands, solution, 140., 50., &font_settings.active, &font_settings.idle, 
-        rings,
-        demon.clone()
+        &level.rings,
+        demon
     );
 
     solution.current_layer_start_time = time.elapsed_seconds();
@@ -333,20 +607,19 @@ fn spawn_puzzle(
     spacing: f32,
     active_text_style: &TextStyle,
     idle_text_style: &TextStyle,
-    rings: &Vec<(&str, usize, usize)>,
+    rings: &[Ring],
     demon: Handle<Image>,
 ) {
     let mut cur_radius = base_radius;
     let active = 0;
-    let mut index = 0;
-    for (word, step, start) in rings.iter() {
+    for (index, ring) in rings.iter().enumerate() {
         let is_active = active == index;
-        spawn_ring(commands, cur_radius, if is_active { active_text_style } else { idle_text_style }, is_active, word, step, start, index);
+        spawn_ring(commands, cur_radius, if is_active { active_text_style } else { idle_text_style }, is_active, &ring.word, &ring.step, &ring.start, index as u32);
         cur_radius += spacing;
 
-        solution.complete_solution.push(word.to_string());
-
-        index += 1;
+        solution.complete_solution.push(ring.word.clone());
+        solution.misses.push(0);
+        solution.hints.push(0);
     }
 
     solution.target_word = solution.complete_solution[0].clone();
@@ -357,6 +630,49 @@ fn spawn_puzzle(
             ..default()
         },
         WordDisplay {},
+        SquishEffect::new(Vec3::ONE, Vec3::new(1.4, 0.6, 1.), 0.05, 0.1, 0.3),
+        ShakeEffect::new(Vec3::new(0., -300., 0.), 12., 15., 0.4),
+        LevelObject {},
+    ));
+
+    commands.spawn((
+        Text2dBundle {
+            transform: Transform::from_translation(Vec3::new(0., 285., 0.)),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        HintDisplay {},
+        LevelObject {},
+    ));
+
+    commands.spawn((
+        ShapeBundle {
+            spatial: SpatialBundle {
+                transform: Transform::from_translation(Vec3::new(0., 0., -0.1)),
+                ..default()
+            },
+            ..default()
+        },
+        Stroke::new(WORD_LINE_COLOR.with_a(0.6), 3.0),
+        PointerLine {},
+        LevelObject {},
+    ));
+
+    commands.spawn((
+        ShapeBundle {
+            spatial: SpatialBundle {
+                transform: Transform::from_translation(Vec3::new(0., 0., -0.3)),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            path: GeometryBuilder::build_as(&shapes::Circle {
+                radius: HOVER_RING_RADIUS,
+                center: Vec2::ZERO,
+            }),
+            ..default()
+        },
+        Stroke::new(WORD_LINE_COLOR, 2.0),
+        HoverRing {},
         LevelObject {},
     ));
 
@@ -387,26 +703,18 @@ fn spawn_ring(
     layer: u32,
 ) {
     let parent = commands.spawn((TransformBundle::default(), RingLayer { layer }, InheritedVisibility::default(), LevelObject {})).id();
-    let length = word.len() - 1;
-
-    let mut shuffled_word = vec![0; length];
+    let layout = RingLayout::new(word, *solution_step, *solution_start_index).expect("levels are validated before they are played");
 
-    for i in 0..length {
-        let wrap_index = i * solution_step;
-        let shuffled_index = wrap_index % length;
-        shuffled_word[shuffled_index] = word.as_bytes()[i];
-    }
-
-    for i in 0..length {
-        let angle = i as f32 * (2. * PI / length as f32);
+    for (i, letter) in layout.letters.iter().enumerate() {
+        let angle = layout.angle(i);
         let offset_direction = Vec2::from_angle(angle);
         let offset = offset_direction * radius;
 
         let character = if active {
-            (shuffled_word[(i + solution_start_index) % length] as char).to_ascii_uppercase()
+            *letter
         }
         else {  
-            (shuffled_word[(i + solution_start_index) % length] as char).to_ascii_lowercase()
+            letter.to_ascii_lowercase()
         };
 
         commands.spawn((Text2dBundle {
@@ -422,6 +730,7 @@ fn spawn_ring(
                 layer
             },
             SquishEffect::new(Vec3::ONE, Vec3::splat(2.), 0.01, 0., 0.25),
+            ShakeEffect::new(offset.extend(-0.2), 6., 20., 0.3),
         )).set_parent(parent);
     }
 
@@ -453,11 +762,25 @@ fn spawn_ring(
             },
             ..default()
         },
-        Stroke::new(Color::rgb(1.0, 0.3, 0.3), 4.0),
+        Stroke::new(WORD_LINE_COLOR, 4.0),
         WordLine {
             layer,
         }
     )).set_parent(parent);
+
+    commands.spawn((
+        ShapeBundle {
+            spatial: SpatialBundle {
+                transform: Transform::from_translation(Vec3::new(0., 0., -0.15)),
+                ..default()
+            },
+            ..default()
+        },
+        Stroke::new(HINT_COLOR, 2.0),
+        HintLine {
+            layer,
+        }
+    )).set_parent(parent);
 }
 
 fn update_active_ring(
@@ -466,10 +789,12 @@ fn update_active_ring(
     mut letters: Query<(&mut Text, &mut LetterDisplay)>,
     mut rings: Query<(&mut Stroke, &LayerRing)>,
     text_styles: Res<RuneTextStyles>,
-    mut reset_button: Query<(&mut ResetButton, &mut Visibility), (Without<BackspaceButton>, Without<NextLevelButton>)>,
-    mut backspace_button: Query<(&mut BackspaceButton, &mut Visibility), (Without<ResetButton>, Without<NextLevelButton>)>,
-    mut next_level_button: Query<(&mut NextLevelButton, &mut Visibility), (Without<BackspaceButton>, Without<ResetButton>)>,
+    mut reset_button: Query<(&mut ResetButton, &mut Visibility), (Without<BackspaceButton>, Without<NextLevelButton>, Without<HintButton>)>,
+    mut backspace_button: Query<(&mut BackspaceButton, &mut Visibility), (Without<ResetButton>, Without<NextLevelButton>, Without<HintButton>)>,
+    mut next_level_button: Query<(&mut NextLevelButton, &mut Visibility), (Without<BackspaceButton>, Without<ResetButton>, Without<HintButton>)>,
+    mut hint_button: Query<(&mut HintButton, &mut Visibility), (Without<BackspaceButton>, Without<ResetButton>, Without<NextLevelButton>)>,
     time: Res<Time>,
+    mut next_state: ResMut<NextState<GameState>>,
 ) {
     for completion in complete_events.read() {
         println!("completed! advancing to {}", completion.now_on_layer);
@@ -519,11 +844,19 @@ fn update_active_ring(
         b_button.active = in_gameplay_step;
         *b_vis = gameplay_button_vis;
 
+        let (mut h_button, mut h_vis) = hint_button.single_mut();
+        h_button.active = in_gameplay_step;
+        *h_vis = gameplay_button_vis;
+
         let show_next_button = (new_active as usize) == selection.complete_solution.len();
         let (mut nl_button, mut nl_vis) = next_level_button.single_mut();
         nl_button.active = show_next_button;
         *nl_vis = if show_next_button { Visibility::Visible } else { Visibility::Hidden };
 
+        if show_next_button {
+            next_state.set(GameState::DemonReveal);
+        }
+
     }
 }
 
@@ -534,61 +867,149 @@ fn update_mouse_position(
     mouse_buttons: Res<ButtonInput<MouseButton>>,
     touches: Res<Touches>,
 ) {
-    if let Some(viewport_position) = windows.single().cursor_position() {
-        let (camera, camera_transform) = cameras.single();
+    let (camera, camera_transform) = cameras.single();
 
+    if let Some(viewport_position) = windows.single().cursor_position() {
         mouse_pos_state.pos = camera.viewport_to_world_2d(camera_transform, viewport_position);
         mouse_pos_state.just_clicked = mouse_buttons.just_pressed(MouseButton::Left);
+        mouse_pos_state.pressed = mouse_buttons.pressed(MouseButton::Left);
+        mouse_pos_state.just_released = mouse_buttons.just_released(MouseButton::Left);
     }
-    else if touches.any_just_pressed() {
-        if let Some(viewport_position) = touches.first_pressed_position() {
-            let (camera, camera_transform) = cameras.single();
-            mouse_pos_state.pos = camera.viewport_to_world_2d(camera_transform, viewport_position);
-            mouse_pos_state.just_clicked = true;
-        }
-        else {
-            mouse_pos_state.pos = None;
-            mouse_pos_state.just_clicked = false;
-        }
+    else if let Some(touch) = touches.iter().next() {
+        // follow the finger for as long as it's down so runes can be traced
+        mouse_pos_state.pos = camera.viewport_to_world_2d(camera_transform, touch.position());
+        mouse_pos_state.just_clicked = touches.just_pressed(touch.id());
+        mouse_pos_state.pressed = true;
+        mouse_pos_state.just_released = false;
+    }
+    else if let Some(touch) = touches.iter_just_released().next() {
+        mouse_pos_state.pos = camera.viewport_to_world_2d(camera_transform, touch.position());
+        mouse_pos_state.just_clicked = false;
+        mouse_pos_state.pressed = false;
+        mouse_pos_state.just_released = true;
     }
     else {
         mouse_pos_state.pos = None;
         mouse_pos_state.just_clicked = false;
+        mouse_pos_state.pressed = false;
+        mouse_pos_state.just_released = false;
     }
 }
 
-fn select_letters(
-    mut selection: ResMut<WordSelection>,
-    mut letters: Query<(&LetterDisplay, &Transform, &mut SquishEffect)>,
-    mouse_state: Res<MousePosition>,
+#[derive(Default)]
+struct TraceState {
+    // pressed down on a rune and still held
+    active: bool,
+    // runes were added or taken back by dragging, not just tapped
+    dragged: bool,
+    layer: u32,
+    // the rune last refused while dragging, so hovering over it only shakes once
+    refused: Option<Vec2>,
+}
+
+fn read_typed_letters(
+    mut typed: ResMut<TypedLetters>,
     mut character_events: EventReader<ReceivedCharacter>,
 ) {
-    let mut recieved_chars = Vec::new();
+    typed.letters.clear();
     for ev in character_events.read() {
-        recieved_chars.push((ev.char.as_bytes()[0] as char).to_ascii_uppercase());
+        typed.letters.push((ev.char.as_bytes()[0] as char).to_ascii_uppercase());
     }
-    let recieved_chars = recieved_chars;
+}
+
+fn select_letters(
+    mut selection: ResMut<WordSelection>,
+    mut letters: Query<(&LetterDisplay, &Transform, &mut SquishEffect, &mut ShakeEffect)>,
+    mouse_state: Res<MousePosition>,
+    typed: Res<TypedLetters>,
+    rules: Res<SelectionRules>,
+    mut rejected_writer: EventWriter<WordRejectedEvent>,
+    mut trace: Local<TraceState>,
+) {
 
     let mut changed_this_frame = false;
+    let ring_size = selection.target_word.len() - 1;
+
+    // a solved ring ends the trace, the next one starts with a fresh press
+    if trace.layer != selection.current_layer {
+        *trace = TraceState { layer: selection.current_layer, ..default() };
+    }
+
+    if mouse_state.just_clicked {
+        trace.active = false;
+        trace.dragged = false;
+        trace.refused = None;
+    }
+
+    let dragging = trace.active && mouse_state.pressed && !mouse_state.just_clicked;
 
-    for (letter, transform, mut squish) in letters.iter_mut() {
+    for (letter, transform, mut squish, mut shake) in letters.iter_mut() {
         if letter.active {
             let mut mouse_selected = false;
 
             if mouse_state.just_clicked {
                 if let Some(mouse_pos) = mouse_state.pos {
                     if transform.translation.truncate().distance(mouse_pos) < letter.radius {
+                        trace.active = true;
+                        // pressing on the last rune picks the trace back up from there, unless it can be tapped again
+                        let is_last = selection.positions.last() == Some(&letter.position);
+                        mouse_selected = !is_last || rules.rune_usage == RuneUsage::Reuse;
+                    }
+                }
+            }
+
+            if dragging {
+                if let Some(mouse_pos) = mouse_state.pos {
+                    // tighter than a tap so passing close to a neighbour doesn't pick it up
+                    let over_rune = transform.translation.truncate().distance(mouse_pos) < letter.radius * TRACE_RADIUS_SCALE;
+                    let is_last = selection.positions.last() == Some(&letter.position);
+
+                    if over_rune && !is_last && trace.refused != Some(letter.position) {
+                        let count = selection.positions.len();
+
+                        if count >= 2 && selection.positions[count - 2] == letter.position {
+                            // dragging back over the previous rune takes the last one off
+                            selection.built_word.pop();
+                            selection.positions.pop();
+
+                            trace.dragged = true;
+                            changed_this_frame = true;
+
+                            squish.reset();
+                            continue;
+                        }
+
                         mouse_selected = true;
                     }
                 }
             }
 
-            let keyboard_selected = recieved_chars.contains(&letter.letter);
+            let keyboard_selected = typed.letters.contains(&letter.letter);
             
             if mouse_selected || keyboard_selected {
+                if let Err(violation) = rules.check_tap(&selection.built_word, letter.letter, ring_size) {
+                    println!("can't select {}: {}", letter.letter, violation);
+                    shake.reset();
+
+                    if dragging {
+                        trace.refused = Some(letter.position);
+                        continue;
+                    }
+
+                    if let Some(SolveAttempt::Rejected { word, reason }) = SolveAttempt::refused_tap(&selection.built_word, letter.letter, ring_size, violation) {
+                        rejected_writer.send(WordRejectedEvent { word, reason });
+                    }
+                    continue;
+                }
+
                 selection.built_word.push(letter.letter);
                 selection.positions.push(letter.position);
 
+                if dragging {
+                    trace.dragged = true;
+                    trace.refused = None;
+                }
+
                 changed_this_frame = true;
 
                 squish.reset();
@@ -596,26 +1017,136 @@ fn select_letters(
         }
     }
 
+    // letting go of a drag submits whatever was traced, a finished word has been checked already
+    if mouse_state.just_released && trace.active {
+        if trace.dragged && !selection.built_word.is_empty() {
+            if rules.finished_word(&selection.built_word, ring_size).is_none() {
+                let reason = match rules.check_word(&selection.built_word, ring_size) {
+                    Err(violation) => RejectReason::BrokenRule(violation),
+                    Ok(()) => RejectReason::NotAWord,
+                };
+                rejected_writer.send(WordRejectedEvent { word: selection.built_word.clone(), reason });
+            }
+
+            selection.built_word.clear();
+            selection.positions.clear();
+            changed_this_frame = true;
+        }
+
+        trace.active = false;
+        trace.dragged = false;
+    }
+
+    if selection.tracing != trace.active {
+        selection.tracing = trace.active;
+    }
     selection.changed_this_frame = changed_this_frame;
 }
 
 fn check_complete(
-    selection: ResMut<WordSelection>,
+    mut selection: ResMut<WordSelection>,
     word_list: Res<WordList>,
+    rules: Res<SelectionRules>,
     mut complete_writer: EventWriter<WordCompleteEvent>,
+    mut rejected_writer: EventWriter<WordRejectedEvent>,
+    time: Res<Time>,
 ) {
-    let bytes = selection.built_word.as_bytes();
+    let dead_end = SolveAttempt::is_dead_end(&selection.built_word, &selection.target_word, &word_list);
+    if selection.dead_end != dead_end {
+        selection.dead_end = dead_end;
+    }
 
-    if selection.built_word.len() >= selection.target_word.len() && bytes[0].to_ascii_uppercase() == bytes[bytes.len() - 1].to_ascii_uppercase() {
-        println!("checking if {} is valid solution for {}", selection.built_word, selection.target_word);
-        if selection.built_word.to_ascii_uppercase() == selection.target_word.to_ascii_uppercase() {
+    if !selection.changed_this_frame {
+        return;
+    }
+
+    let Some(attempt) = SolveAttempt::check(&selection.built_word, &selection.target_word, &rules, &word_list) else {
+        return;
+    };
+
+    println!("checking if {:?} is valid solution for {}", attempt, selection.target_word);
+    let seconds = time.elapsed_seconds() - selection.current_layer_start_time;
+
+    match attempt {
+        SolveAttempt::Perfect(word) => {
             complete_writer.send(WordCompleteEvent { now_on_layer: selection.current_layer + 1});
+            selection.solves.push(RingSolve { word, perfect: true, seconds });
             println!("perfect solve!");
         }
-        else if word_list.all_valid_words.contains(&selection.built_word) {
+        SolveAttempt::Alternate(word) => {
             complete_writer.send(WordCompleteEvent { now_on_layer: selection.current_layer + 1});
+            selection.solves.push(RingSolve { word, perfect: false, seconds });
             println!("alternate solve!");
         }
+        SolveAttempt::Rejected { word, reason } => {
+            rejected_writer.send(WordRejectedEvent { word, reason });
+        }
+    }
+}
+
+fn react_to_rejection(
+    mut selection: ResMut<WordSelection>,
+    mut rejected_events: EventReader<WordRejectedEvent>,
+    mut display_query: Query<(&mut SquishEffect, &mut ShakeEffect), With<WordDisplay>>,
+) {
+    for rejection in rejected_events.read() {
+        println!("rejected {}: {}", rejection.word, rejection.reason);
+
+        let layer = selection.current_layer as usize;
+        if let Some(misses) = selection.misses.get_mut(layer) {
+            *misses += 1;
+        }
+
+        let (mut squish, mut shake) = display_query.single_mut();
+        squish.reset();
+        shake.reset();
+    }
+}
+
+// Runs ahead of check_complete so the cleared trace is checked and drawn the same frame
+fn clear_dead_end(
+    mut selection: ResMut<WordSelection>,
+    rules: Res<SelectionRules>,
+    time: Res<Time>,
+    mut dead_end_since: Local<Option<f32>>,
+) {
+    let Some(delay) = rules.clear_dead_ends_after else {
+        return;
+    };
+
+    if !selection.dead_end {
+        *dead_end_since = None;
+        return;
+    }
+
+    let now = time.elapsed_seconds();
+    let since = *dead_end_since.get_or_insert(now);
+
+    if now - since > delay {
+        selection.built_word.clear();
+        selection.positions.clear();
+        selection.changed_this_frame = true;
+        *dead_end_since = None;
+    }
+}
+
+fn show_dead_end(
+    selection: Res<WordSelection>,
+    mut line_query: Query<(&WordLine, &mut Stroke)>,
+    mut display_query: Query<&mut ShakeEffect, With<WordDisplay>>,
+    mut was_dead_end: Local<bool>,
+) {
+    if selection.dead_end && !*was_dead_end {
+        display_query.single_mut().reset();
+    }
+    *was_dead_end = selection.dead_end;
+
+    let line_color = if selection.dead_end { DEAD_END_LINE_COLOR } else { WORD_LINE_COLOR };
+
+    for (word_line, mut stroke) in line_query.iter_mut() {
+        if word_line.layer == selection.current_layer && stroke.color != line_color {
+            stroke.color = line_color;
+        }
     }
 }
 
@@ -667,6 +1198,126 @@ fn handle_next_level(
     }
 }
 
+fn handle_hint(
+    mut selection: ResMut<WordSelection>,
+    mut button_query: Query<(&Transform, &HintButton, &mut SquishEffect)>,
+    mouse_pos: Res<MousePosition>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    let mut is_clicked = false;
+
+    let (transform, button, mut squish) = button_query.single_mut();
+
+    if button.active {
+        if let Some(pos) = mouse_pos.pos {
+            let button_pos = transform.translation.truncate();
+            if mouse_pos.just_clicked && pos.distance(button_pos) < 50. {
+                is_clicked = true;
+            }
+        }
+
+        if is_clicked || keys.just_pressed(KeyCode::Slash) {
+            let layer = selection.current_layer as usize;
+            if let Some(hint_level) = selection.hints.get_mut(layer) {
+                if *hint_level < MAX_HINT_LEVEL {
+                    *hint_level += 1;
+                    println!("hint {} for layer {}", hint_level, layer);
+
+                    squish.reset();
+                }
+            }
+        }
+    }
+}
+
+// Points of the hint line: the next correct segment after the correct part of the built word,
+// or the whole loop once the word is revealed
+fn hint_path(target: &str, built_word: &str, hint_level: u32, positions: &HashMap<char, Vec2>) -> Vec<Vec2> {
+    let target: Vec<char> = target.to_ascii_uppercase().chars().collect();
+    let points = |from: usize, to: usize| -> Vec<Vec2> {
+        target[from..to].iter().filter_map(|c| positions.get(c).copied()).collect()
+    };
+
+    match hint_level {
+        0 | 1 => Vec::new(),
+        2 => {
+            let correct = built_word.chars().zip(target.iter()).take_while(|(built, wanted)| built == *wanted).count();
+            if correct >= target.len() {
+                Vec::new()
+            }
+            else {
+                let from = correct.saturating_sub(1);
+                points(from, (from + 2).min(target.len()))
+            }
+        }
+        _ => points(0, target.len()),
+    }
+}
+
+fn show_hints(
+    selection: Res<WordSelection>,
+    mut letters: Query<(&LetterDisplay, &mut Text), Without<HintDisplay>>,
+    mut hint_lines: Query<(&HintLine, &mut Path)>,
+    mut hint_display: Query<(&mut Text, &mut Visibility), With<HintDisplay>>,
+    text_styles: Res<RuneTextStyles>,
+    mut shown: Local<(u32, u32, String)>,
+) {
+    let layer = selection.current_layer;
+    let hint_level = selection.hints.get(layer as usize).copied().unwrap_or(0);
+
+    let key = (layer, hint_level, selection.built_word.clone());
+    if *shown == key {
+        return;
+    }
+    *shown = key;
+
+    let first_letter = selection.target_word.chars().next().map(|c| c.to_ascii_uppercase());
+    let mut positions = HashMap::new();
+
+    for (letter, mut text) in letters.iter_mut() {
+        if !letter.active {
+            continue;
+        }
+
+        positions.insert(letter.letter, letter.position);
+
+        let highlight = hint_level >= 1 && first_letter == Some(letter.letter);
+        let color = if highlight { HINT_COLOR } else { text_styles.active.color };
+        if text.sections[0].style.color != color {
+            text.sections[0].style.color = color;
+        }
+    }
+
+    for (hint_line, mut path) in hint_lines.iter_mut() {
+        let points = if hint_line.layer == layer {
+            hint_path(&selection.target_word, &selection.built_word, hint_level, &positions)
+        }
+        else {
+            Vec::new()
+        };
+
+        let mut path_builder = PathBuilder::new();
+        if points.len() > 1 {
+            path_builder.move_to(points[0]);
+
+            for point in points.iter().skip(1) {
+                path_builder.line_to(*point);
+            }
+        }
+
+        *path = path_builder.build();
+    }
+
+    let (mut text, mut vis) = hint_display.single_mut();
+    if hint_level >= MAX_HINT_LEVEL {
+        *text = Text::from_section(selection.target_word.to_ascii_uppercase(), TextStyle { color: HINT_COLOR, ..text_styles.active.clone() });
+        *vis = Visibility::Visible;
+    }
+    else {
+        *vis = Visibility::Hidden;
+    }
+}
+
 fn handle_reset(
     mut selection: ResMut<WordSelection>,
     mut button_query: Query<(&Transform, &ResetButton, &mut SquishEffect)>,
@@ -712,14 +1363,54 @@ fn handle_backspace(
             }
         }
     
-        if is_clicked || keys.just_pressed(KeyCode::Backspace) {
-            if selection.built_word.len() > 0 {
-                selection.built_word.pop();
-                selection.positions.pop();
-                selection.changed_this_frame = true;
+        if (is_clicked || keys.just_pressed(KeyCode::Backspace)) && !selection.built_word.is_empty() {
+            selection.built_word.pop();
+            selection.positions.pop();
+            selection.changed_this_frame = true;
 
-                squish.reset();
+            squish.reset();
+        }
+    }
+}
+
+fn draw_pointer(
+    selection: Res<WordSelection>,
+    mouse_pos: Res<MousePosition>,
+    letters: Query<(&LetterDisplay, &Transform), Without<HoverRing>>,
+    mut pointer_lines: Query<&mut Path, With<PointerLine>>,
+    mut hover_rings: Query<(&mut Transform, &mut Visibility), With<HoverRing>>,
+) {
+    let pointer = mouse_pos.pos;
+
+    // while tracing, stretch a line from the last rune out to the pointer
+    let mut path_builder = PathBuilder::new();
+    if let (true, Some(last), Some(pointer)) = (selection.tracing, selection.positions.last(), pointer) {
+        path_builder.move_to(*last);
+        path_builder.line_to(pointer);
+    }
+    if let Ok(mut path) = pointer_lines.get_single_mut() {
+        *path = path_builder.build();
+    }
+
+    // ring the rune a tap or the trace would pick up
+    let hovered = pointer.and_then(|pointer| {
+        letters.iter()
+            .filter(|(letter, _)| letter.active)
+            .map(|(letter, transform)| {
+                let radius = if selection.tracing { letter.radius * TRACE_RADIUS_SCALE } else { letter.radius };
+                (transform.translation.truncate(), transform.translation.truncate().distance(pointer), radius)
+            })
+            .filter(|(_, distance, radius)| distance < radius)
+            .min_by(|(_, a, _), (_, b, _)| a.total_cmp(b))
+    });
+
+    for (mut transform, mut visibility) in hover_rings.iter_mut() {
+        match hovered {
+            Some((position, _, _)) => {
+                transform.translation = position.extend(transform.translation.z);
+                *visibility = Visibility::Visible;
             }
+            None => *visibility = Visibility::Hidden,
         }
     }
 }
@@ -0,0 +1,62 @@
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy::utils::BoxedFuture;
+use puzzle_core::*;
+
+pub const DEFAULT_PUZZLE_PACK: &str = "puzzles/default.puzzles.ron";
+
+pub const DEMON_SPRITES: [&str; 5] = [
+    "sprites/demon01.png",
+    "sprites/demon02.png",
+    "sprites/demon03.png",
+    "sprites/demon04.png",
+    "sprites/demon05.png",
+];
+
+// The pack the game ships with, read straight from the assets folder for tests that don't run the asset server
+#[cfg(test)]
+pub fn shipped_pack() -> PuzzlePack {
+    PuzzlePack::from_ron(include_bytes!("../assets/puzzles/default.puzzles.ron"), DEMON_SPRITES.len()).expect("default pack should load")
+}
+
+#[derive(Resource)]
+pub struct PuzzlesList {
+    pub list: Vec<Puzzle>,
+    pub current: usize,
+}
+
+#[derive(Resource)]
+pub struct PuzzlePackHandle(pub Handle<PuzzlePack>);
+
+#[derive(Default)]
+pub struct PuzzlePackLoader;
+
+impl AssetLoader for PuzzlePackLoader {
+    type Asset = PuzzlePack;
+    type Settings = ();
+    type Error = PuzzlePackError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<PuzzlePack, PuzzlePackError>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            PuzzlePack::from_ron(&bytes, DEMON_SPRITES.len())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["puzzles.ron"]
+    }
+}
+
+pub fn load_puzzle_pack(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands.insert_resource(PuzzlePackHandle(asset_server.load(DEFAULT_PUZZLE_PACK)));
+}
@@ -0,0 +1,309 @@
+use bevy::prelude::*;
+use puzzle_core::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use thiserror::Error;
+
+use crate::daily::*;
+use crate::puzzles::*;
+use crate::scoring::*;
+use crate::WordSelection;
+
+#[derive(Debug, Error)]
+pub enum SaveError {
+    #[error("could not access save file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not read save data: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("could not write save data: {0}")]
+    Write(#[from] ron::Error),
+    #[cfg(target_arch = "wasm32")]
+    #[error("save storage is not available")]
+    Unavailable,
+}
+
+// Where save data lives: a file natively, local storage in the browser
+pub trait SaveStorage: Send + Sync {
+    fn load(&self) -> Result<Option<String>, SaveError>;
+    fn save(&self, contents: &str) -> Result<(), SaveError>;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileStorage {
+    pub path: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SaveStorage for FileStorage {
+    fn load(&self) -> Result<Option<String>, SaveError> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(contents) => Ok(Some(contents)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn save(&self, contents: &str) -> Result<(), SaveError> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        // write then rename so a crash mid-save can't leave half a file behind
+        let temp_path = self.path.with_extension("tmp");
+        std::fs::write(&temp_path, contents)?;
+        std::fs::rename(&temp_path, &self.path)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub struct LocalStorage {
+    pub key: String,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorage {
+    fn storage() -> Result<web_sys::Storage, SaveError> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or(SaveError::Unavailable)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl SaveStorage for LocalStorage {
+    fn load(&self) -> Result<Option<String>, SaveError> {
+        Self::storage()?.get_item(&self.key).map_err(|_| SaveError::Unavailable)
+    }
+
+    fn save(&self, contents: &str) -> Result<(), SaveError> {
+        Self::storage()?.set_item(&self.key, contents).map_err(|_| SaveError::Unavailable)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn data_dir() -> std::path::PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+        .join("summoners-word")
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn default_storage() -> Box<dyn SaveStorage> {
+    let path = std::env::var_os("SUMMONERS_WORD_SAVE")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| data_dir().join("save.ron"));
+
+    Box::new(FileStorage { path })
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn default_storage() -> Box<dyn SaveStorage> {
+    Box::new(LocalStorage { key: "summoners-word-save".to_string() })
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct LevelProgress {
+    pub completed: bool,
+    pub best_time: Option<f32>,
+    pub fewest_hints: Option<u32>,
+    #[serde(default)]
+    pub best_score: Option<u32>,
+    pub alternate_words: BTreeSet<String>,
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct SaveData {
+    pub current_level: usize,
+    // keyed by level_key so progress follows a level when the pack is reordered
+    pub levels: BTreeMap<String, LevelProgress>,
+    #[serde(default)]
+    pub daily: DailyProgress,
+}
+
+pub fn level_key(level: &Puzzle) -> String {
+    level.rings.iter()
+        .map(|ring| ring.word.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+impl SaveData {
+    pub fn from_ron(contents: &str) -> Result<SaveData, SaveError> {
+        Ok(ron::de::from_str(contents)?)
+    }
+
+    pub fn to_ron(&self) -> Result<String, SaveError> {
+        Ok(ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?)
+    }
+
+    pub fn level(&self, level: &Puzzle) -> Option<&LevelProgress> {
+        self.levels.get(&level_key(level))
+    }
+
+    pub fn is_completed(&self, level: &Puzzle) -> bool {
+        self.level(level).is_some_and(|progress| progress.completed)
+    }
+
+    // The first level is always open, after that a level opens once the one before it is beaten
+    pub fn is_unlocked(&self, levels: &[Puzzle], index: usize) -> bool {
+        index == 0 || self.is_completed(&levels[index]) || self.is_completed(&levels[index - 1])
+    }
+
+    pub fn record_completion(&mut self, level: &Puzzle, seconds: f32, hints: u32, score: u32, alternate_words: &[String]) {
+        let progress = self.levels.entry(level_key(level)).or_default();
+
+        progress.completed = true;
+        progress.best_time = Some(progress.best_time.map_or(seconds, |best| best.min(seconds)));
+        progress.fewest_hints = Some(progress.fewest_hints.map_or(hints, |fewest| fewest.min(hints)));
+        progress.best_score = Some(progress.best_score.map_or(score, |best| best.max(score)));
+        progress.alternate_words.extend(alternate_words.iter().map(|word| word.to_ascii_uppercase()));
+    }
+}
+
+#[derive(Resource)]
+pub struct SaveStore {
+    pub storage: Box<dyn SaveStorage>,
+}
+
+impl SaveStore {
+    pub fn load(&self) -> SaveData {
+        let contents = match self.storage.load() {
+            Ok(Some(contents)) => contents,
+            Ok(None) => return SaveData::default(),
+            Err(err) => {
+                println!("{}, starting without saved progress", err);
+                return SaveData::default();
+            }
+        };
+
+        SaveData::from_ron(&contents).unwrap_or_else(|err| {
+            println!("{}, starting without saved progress", err);
+            SaveData::default()
+        })
+    }
+
+    pub fn write(&self, save_data: &SaveData) {
+        if let Err(err) = save_data.to_ron().and_then(|contents| self.storage.save(&contents)) {
+            println!("{}", err);
+        }
+    }
+}
+
+pub fn record_progress(
+    selection: Res<WordSelection>,
+    score: Res<LevelScore>,
+    puzzles_list: Res<PuzzlesList>,
+    mode: Res<PlayMode>,
+    mut save_data: ResMut<SaveData>,
+    save_store: Res<SaveStore>,
+) {
+    match *mode {
+        PlayMode::Campaign => {}
+        PlayMode::Daily { day, .. } => {
+            save_data.daily.record(day, score.total());
+            save_store.write(&save_data);
+            return;
+        }
+        // shared levels aren't part of any progress
+        PlayMode::Shared { .. } => return,
+    }
+
+    let level = &puzzles_list.list[puzzles_list.current];
+
+    let seconds = selection.solves.iter().map(|solve| solve.seconds).sum();
+    let hints = selection.hints.iter().sum();
+    let alternates: Vec<String> = selection.solves.iter()
+        .filter(|solve| !solve.perfect)
+        .map(|solve| solve.word.clone())
+        .collect();
+
+    save_data.record_completion(level, seconds, hints, score.total(), &alternates);
+    save_store.write(&save_data);
+}
+
+pub fn remember_current_level(
+    puzzles_list: Res<PuzzlesList>,
+    mut save_data: ResMut<SaveData>,
+    save_store: Res<SaveStore>,
+) {
+    if save_data.current_level != puzzles_list.current {
+        save_data.current_level = puzzles_list.current;
+        save_store.write(&save_data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the save directory is deleted when the returned TempDir is dropped
+    fn test_storage(name: &str) -> (tempfile::TempDir, FileStorage) {
+        let dir = tempfile::Builder::new().prefix(&format!("summoners-word-test-{}-", name)).tempdir().unwrap();
+        let path = dir.path().join("save.ron");
+
+        (dir, FileStorage { path })
+    }
+
+    #[test]
+    fn missing_save_file_loads_nothing() {
+        let (_dir, storage) = test_storage("missing");
+
+        assert!(storage.load().unwrap().is_none());
+        assert_eq!(SaveStore { storage: Box::new(storage) }.load(), SaveData::default());
+    }
+
+    #[test]
+    fn file_storage_round_trips_progress() {
+        let (_dir, storage) = test_storage("round-trip");
+        let store = SaveStore { storage: Box::new(storage) };
+
+        let mut save_data = SaveData { current_level: 3, ..default() };
+        save_data.record_completion(&Puzzle::from_rings(0, &[("lethal", 1, 0)]), 12.5, 1, 180, &["HEALTH".to_string()]);
+        store.write(&save_data);
+
+        assert_eq!(store.load(), save_data);
+    }
+
+    #[test]
+    fn completions_keep_the_best_results() {
+        let lethal = Puzzle::from_rings(0, &[("lethal", 1, 0), ("rocker", 1, 0)]);
+        let mut save_data = SaveData::default();
+
+        save_data.record_completion(&lethal, 30., 2, 150, &["health".to_string()]);
+        save_data.record_completion(&lethal, 45., 0, 120, &[]);
+
+        let progress = &save_data.levels[&level_key(&lethal)];
+        assert!(progress.completed);
+        assert_eq!(progress.best_time, Some(30.));
+        assert_eq!(progress.fewest_hints, Some(0));
+        assert_eq!(progress.best_score, Some(150));
+        assert_eq!(progress.alternate_words, BTreeSet::from(["HEALTH".to_string()]));
+    }
+
+    #[test]
+    fn beating_a_level_unlocks_the_next() {
+        let levels = [Puzzle::from_rings(0, &[("lethal", 1, 0)]), Puzzle::from_rings(0, &[("rocker", 1, 0)]), Puzzle::from_rings(0, &[("mayhem", 1, 0)])];
+        let mut save_data = SaveData::default();
+
+        assert!(save_data.is_unlocked(&levels, 0));
+        assert!(!save_data.is_unlocked(&levels, 1));
+
+        save_data.record_completion(&levels[0], 10., 0, 200, &[]);
+        assert!(save_data.is_unlocked(&levels, 1));
+        assert!(!save_data.is_unlocked(&levels, 2));
+
+        // progress from an older ordering of the pack keeps its level open
+        save_data.record_completion(&levels[2], 10., 0, 200, &[]);
+        assert!(save_data.is_unlocked(&levels, 2));
+    }
+
+    #[test]
+    fn corrupt_save_starts_fresh() {
+        let (_dir, storage) = test_storage("corrupt");
+        storage.save("not a save").unwrap();
+
+        assert_eq!(SaveStore { storage: Box::new(storage) }.load(), SaveData::default());
+    }
+}
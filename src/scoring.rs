@@ -0,0 +1,142 @@
+use bevy::prelude::*;
+
+use crate::daily::*;
+use crate::save::*;
+use crate::puzzles::*;
+use crate::{LevelObject, RuneTextStyles, WordSelection};
+
+const BASE_POINTS: u32 = 100;
+// par time for a ring grows with the number of runes on it
+const PAR_SECONDS_PER_RUNE: f32 = 4.;
+const POINTS_PER_SECOND_UNDER_PAR: f32 = 5.;
+const MISS_PENALTY: u32 = 15;
+const HINT_PENALTY: u32 = 30;
+const PERFECT_BONUS: u32 = 50;
+// finding a different word that closes the loop is rarer than the intended one
+const ALTERNATE_BONUS: u32 = 75;
+const MIN_RING_POINTS: u32 = 10;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RingScore {
+    pub word: String,
+    pub points: u32,
+}
+
+#[derive(Resource, Default)]
+pub struct LevelScore {
+    pub rings: Vec<RingScore>,
+}
+
+impl LevelScore {
+    pub fn total(&self) -> u32 {
+        self.rings.iter().map(|ring| ring.points).sum()
+    }
+}
+
+#[derive(Component)]
+pub struct ScoreDisplay {}
+
+pub fn par_seconds(ring_size: usize) -> f32 {
+    ring_size as f32 * PAR_SECONDS_PER_RUNE
+}
+
+pub fn ring_points(ring_size: usize, seconds: f32, misses: u32, hints: u32, perfect: bool) -> u32 {
+    let time_bonus = ((par_seconds(ring_size) - seconds).max(0.) * POINTS_PER_SECOND_UNDER_PAR) as u32;
+    let solve_bonus = if perfect { PERFECT_BONUS } else { ALTERNATE_BONUS };
+    let penalty = misses * MISS_PENALTY + hints * HINT_PENALTY;
+
+    (BASE_POINTS + time_bonus + solve_bonus).saturating_sub(penalty).max(MIN_RING_POINTS)
+}
+
+pub fn score_solves(
+    selection: Res<WordSelection>,
+    mut score: ResMut<LevelScore>,
+) {
+    // a fresh level clears its solves, start the card over with it
+    if score.rings.len() > selection.solves.len() {
+        score.rings.clear();
+    }
+
+    for ring in score.rings.len()..selection.solves.len() {
+        let solve = &selection.solves[ring];
+        let ring_size = selection.complete_solution[ring].len() - 1;
+        let misses = selection.misses.get(ring).copied().unwrap_or(0);
+        let hints = selection.hints.get(ring).copied().unwrap_or(0);
+
+        let points = ring_points(ring_size, solve.seconds, misses, hints, solve.perfect);
+        println!("{} scored {} points", solve.word, points);
+
+        score.rings.push(RingScore { word: solve.word.clone(), points });
+    }
+}
+
+pub fn show_score(
+    mut commands: Commands,
+    score: Res<LevelScore>,
+    puzzles_list: Res<PuzzlesList>,
+    mode: Res<PlayMode>,
+    save_data: Res<SaveData>,
+    rune_fonts: Res<RuneTextStyles>,
+) {
+    let mut lines: Vec<String> = score.rings.iter()
+        .map(|ring| format!("{} +{}", ring.word.to_ascii_uppercase(), ring.points))
+        .collect();
+
+    lines.push(match &*mode {
+        PlayMode::Campaign => {
+            let best = save_data.level(mode.level(&puzzles_list)).and_then(|progress| progress.best_score);
+            match best {
+                Some(best) if best >= score.total() => format!("SCORE {}  BEST {}", score.total(), best),
+                _ => format!("SCORE {}  NEW BEST", score.total()),
+            }
+        }
+        PlayMode::Daily { day, .. } => {
+            // progress is only saved after this, work out the streak this solve makes
+            let mut daily = save_data.daily.clone();
+            daily.record(*day, score.total());
+            format!("SCORE {}  STREAK {}", score.total(), daily.streak)
+        }
+        PlayMode::Shared { .. } => format!("SCORE {}", score.total()),
+    });
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(lines.join("\n"), rune_fonts.active.clone())
+                .with_justify(JustifyText::Center),
+            // hang down from the top of the screen however many rings there are
+            text_anchor: bevy::sprite::Anchor::TopCenter,
+            transform: Transform::from_translation(Vec3::new(0., 295., 1.)),
+            ..default()
+        },
+        ScoreDisplay {},
+        LevelObject {},
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quick_clean_solves_beat_slow_ones() {
+        let quick = ring_points(6, 5., 0, 0, true);
+        let slow = ring_points(6, 60., 0, 0, true);
+
+        assert_eq!(slow, BASE_POINTS + PERFECT_BONUS);
+        assert!(quick > slow);
+    }
+
+    #[test]
+    fn misses_and_hints_cost_points() {
+        let clean = ring_points(6, 60., 0, 0, true);
+
+        assert_eq!(ring_points(6, 60., 2, 0, true), clean - 2 * MISS_PENALTY);
+        assert_eq!(ring_points(6, 60., 0, 1, true), clean - HINT_PENALTY);
+        assert_eq!(ring_points(6, 60., 20, 3, true), MIN_RING_POINTS);
+    }
+
+    #[test]
+    fn alternate_solves_earn_their_own_bonus() {
+        assert_eq!(ring_points(6, 60., 0, 0, false), BASE_POINTS + ALTERNATE_BONUS);
+    }
+}
@@ -0,0 +1,158 @@
+use bevy::prelude::*;
+
+use crate::daily::*;
+use crate::puzzles::*;
+use crate::scoring::*;
+use crate::{LevelObject, MousePosition, RuneTextStyles, WordSelection};
+
+const MAX_MISS_MARKS: u32 = 5;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RingResult {
+    pub perfect: bool,
+    pub hints: u32,
+    pub misses: u32,
+    pub seconds: f32,
+}
+
+#[derive(Component)]
+pub struct ShareButton {}
+
+impl RingResult {
+    fn mark(&self) -> &'static str {
+        if self.hints > 0 {
+            "🟨"
+        }
+        else if self.perfect {
+            "🟩"
+        }
+        else {
+            "🟪"
+        }
+    }
+}
+
+// Spoiler free summary of a solved level, one square per ring then a line each with time and misses
+pub fn result_text(heading: &str, rings: &[RingResult], score: u32) -> String {
+    let mut lines = vec![
+        format!("{} - {}", heading, score),
+        rings.iter().map(|ring| ring.mark()).collect::<String>(),
+    ];
+
+    for ring in rings {
+        let misses = "🟥".repeat(ring.misses.min(MAX_MISS_MARKS) as usize);
+        lines.push(format!("{} {:.0}s {}", ring.mark(), ring.seconds, misses).trim_end().to_string());
+    }
+
+    lines.join("\n")
+}
+
+pub fn ring_results(selection: &WordSelection) -> Vec<RingResult> {
+    selection.solves.iter()
+        .enumerate()
+        .map(|(ring, solve)| RingResult {
+            perfect: solve.perfect,
+            hints: selection.hints.get(ring).copied().unwrap_or(0),
+            misses: selection.misses.get(ring).copied().unwrap_or(0),
+            seconds: solve.seconds,
+        })
+        .collect()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn export_result(text: &str) {
+    println!("{}", text);
+
+    let path = crate::save::data_dir().join("result.txt");
+    let written = std::fs::create_dir_all(crate::save::data_dir()).and_then(|_| std::fs::write(&path, text));
+    match written {
+        Ok(()) => println!("result saved to {}", path.display()),
+        Err(err) => println!("could not save result: {}", err),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn export_result(text: &str) {
+    // println goes nowhere in the browser, these end up in the console
+    let Some(window) = web_sys::window() else {
+        warn!("no browser window to copy the result to");
+        return;
+    };
+
+    // the browser can refuse, e.g. when the page doesn't have focus
+    let copy = wasm_bindgen_futures::JsFuture::from(window.navigator().clipboard().write_text(text));
+    wasm_bindgen_futures::spawn_local(async move {
+        match copy.await {
+            Ok(_) => info!("result copied to the clipboard"),
+            Err(err) => warn!("could not copy the result: {:?}", err),
+        }
+    });
+}
+
+pub fn spawn_share_button(
+    mut commands: Commands,
+    rune_fonts: Res<RuneTextStyles>,
+) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section("SHARE", rune_fonts.active.clone()),
+            transform: Transform::from_translation(Vec3::new(220., -350., 1.)),
+            ..default()
+        },
+        ShareButton {},
+        LevelObject {},
+    ));
+}
+
+pub fn share_result(
+    selection: Res<WordSelection>,
+    score: Res<LevelScore>,
+    puzzles_list: Res<PuzzlesList>,
+    mode: Res<PlayMode>,
+    button_query: Query<&Transform, With<ShareButton>>,
+    mouse_pos: Res<MousePosition>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    let mut is_clicked = false;
+
+    if let (Some(pos), Ok(transform)) = (mouse_pos.pos, button_query.get_single()) {
+        let button_pos = transform.translation.truncate();
+        if mouse_pos.just_clicked && pos.distance(button_pos) < 60. {
+            is_clicked = true;
+        }
+    }
+
+    if !is_clicked && !keys.just_pressed(KeyCode::KeyC) {
+        return;
+    }
+
+    let heading = match &*mode {
+        PlayMode::Campaign => format!("Summoners Word {}", puzzles_list.current + 1),
+        PlayMode::Daily { day, .. } => format!("Summoners Word Daily {}", day),
+        PlayMode::Shared { code, .. } => format!("Summoners Word {}", code),
+    };
+
+    export_result(&result_text(&heading, &ring_results(&selection), score.total()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn result_grid_marks_each_ring() {
+        let rings = [
+            RingResult { perfect: true, hints: 0, misses: 0, seconds: 12.4 },
+            RingResult { perfect: false, hints: 0, misses: 2, seconds: 31. },
+            RingResult { perfect: true, hints: 1, misses: 9, seconds: 58.6 },
+        ];
+
+        assert_eq!(result_text("Summoners Word Daily 20379", &rings, 420), [
+            "Summoners Word Daily 20379 - 420",
+            "🟩🟪🟨",
+            "🟩 12s",
+            "🟪 31s 🟥🟥",
+            "🟨 59s 🟥🟥🟥🟥🟥",
+        ].join("\n"));
+    }
+}
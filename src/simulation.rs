@@ -0,0 +1,490 @@
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use puzzle_core::*;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::puzzles::*;
+use crate::save::*;
+use crate::squashes::*;
+use crate::states::*;
+use crate::{GameplayPlugin, LetterDisplay, MousePosition, PuzzleCompleteEvent, TypedLetters, WordCompleteEvent, WordRejectedEvent, WordSelection};
+
+// Runs the game without a window: input comes from a script and time moves a fixed step per frame
+const FRAME_TIME: Duration = Duration::from_millis(50);
+const REVEAL_FRAME_LIMIT: usize = 100;
+
+// What the player does on one frame
+#[derive(Clone, Debug, Default)]
+pub struct InputFrame {
+    pub typed: Vec<char>,
+    pub pointer: Pointer,
+    pub keys: Vec<KeyCode>,
+}
+
+// The mouse button or finger on one frame
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Pointer {
+    #[default]
+    Up,
+    // pressed and released within the frame
+    Tap(Vec2),
+    Press(Vec2),
+    // held down and moved here
+    Drag(Vec2),
+    Release(Vec2),
+}
+
+#[derive(Resource, Default)]
+pub struct ScriptedInput {
+    pub frames: VecDeque<InputFrame>,
+}
+
+// Events the game sent, kept across frames so tests can look at them afterwards
+#[derive(Resource, Default)]
+pub struct EventLog {
+    pub completed: Vec<u32>,
+    pub rejected: Vec<(String, RejectReason)>,
+    pub puzzles_completed: usize,
+}
+
+fn play_script(
+    mut script: ResMut<ScriptedInput>,
+    mut mouse_pos: ResMut<MousePosition>,
+    mut typed: ResMut<TypedLetters>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+) {
+    let frame = script.frames.pop_front().unwrap_or_default();
+
+    let (pos, just_clicked, pressed, just_released) = match frame.pointer {
+        Pointer::Up => (None, false, false, false),
+        Pointer::Tap(pos) => (Some(pos), true, false, false),
+        Pointer::Press(pos) => (Some(pos), true, true, false),
+        Pointer::Drag(pos) => (Some(pos), false, true, false),
+        Pointer::Release(pos) => (Some(pos), false, false, true),
+    };
+    mouse_pos.pos = pos;
+    mouse_pos.just_clicked = just_clicked;
+    mouse_pos.pressed = pressed;
+    mouse_pos.just_released = just_released;
+
+    typed.letters = frame.typed;
+
+    keys.release_all();
+    keys.clear();
+    for key in frame.keys {
+        keys.press(key);
+    }
+}
+
+fn log_events(
+    mut log: ResMut<EventLog>,
+    mut completed: EventReader<WordCompleteEvent>,
+    mut rejected: EventReader<WordRejectedEvent>,
+    mut puzzles_completed: EventReader<PuzzleCompleteEvent>,
+) {
+    log.completed.extend(completed.read().map(|event| event.now_on_layer));
+    log.rejected.extend(rejected.read().map(|event| (event.word.clone(), event.reason)));
+    log.puzzles_completed += puzzles_completed.read().count();
+}
+
+pub struct Simulation {
+    pub app: App,
+    // holds the save file; the directory is deleted when the simulation is dropped
+    _save_dir: tempfile::TempDir,
+}
+
+impl Simulation {
+    pub fn new(name: &str) -> Simulation {
+        let pack = shipped_pack();
+
+        let save_dir = tempfile::Builder::new().prefix(&format!("summoners-word-sim-{}-", name)).tempdir().unwrap();
+        let save_path = save_dir.path().join("save.ron");
+
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(GameplayPlugin)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME_TIME))
+            .insert_resource(SaveStore { storage: Box::new(FileStorage { path: save_path }) })
+            .insert_resource(pack.rules)
+            .insert_resource(PuzzlesList { list: pack.levels, current: 0 })
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<ScriptedInput>()
+            .init_resource::<EventLog>()
+            .add_systems(PreUpdate, play_script)
+            .add_systems(Last, log_events);
+
+        app.update();
+
+        Simulation { app, _save_dir: save_dir }
+    }
+
+    pub fn start_level(&mut self, index: usize) {
+        self.app.world.resource_mut::<PuzzlesList>().current = index;
+        self.app.world.resource_mut::<NextState<GameState>>().set(GameState::Playing);
+        self.run_frames(2);
+    }
+
+    pub fn run_frames(&mut self, count: usize) {
+        for _ in 0..count {
+            self.app.update();
+        }
+    }
+
+    // a solved ring is picked up the frame after and changes state the frame after that
+    pub fn settle(&mut self) {
+        self.run_frames(2);
+    }
+
+    pub fn play(&mut self, frame: InputFrame) {
+        self.app.world.resource_mut::<ScriptedInput>().frames.push_back(frame);
+        self.app.update();
+    }
+
+    // one letter a frame, the same as someone typing
+    pub fn type_word(&mut self, word: &str) {
+        for c in word.chars() {
+            self.play(InputFrame { typed: vec![c.to_ascii_uppercase()], ..default() });
+        }
+        self.settle();
+    }
+
+    pub fn press(&mut self, key: KeyCode) {
+        self.play(InputFrame { keys: vec![key], ..default() });
+    }
+
+    pub fn rune_position(&mut self, letter: char) -> Option<Vec2> {
+        let mut letters = self.app.world.query::<(&LetterDisplay, &Transform)>();
+
+        letters.iter(&self.app.world)
+            .find(|(display, _)| display.active && display.letter == letter)
+            .map(|(_, transform)| transform.translation.truncate())
+    }
+
+    pub fn try_click_word(&mut self, word: &str) -> Result<(), String> {
+        for c in word.chars() {
+            let position = self.rune_position(c.to_ascii_uppercase())
+                .ok_or(format!("no rune {} on the ring for {}", c, word))?;
+            self.play(InputFrame { pointer: Pointer::Tap(position), ..default() });
+        }
+        self.settle();
+
+        Ok(())
+    }
+
+    // Presses on the first rune and drags through the rest without letting go
+    pub fn drag_through(&mut self, letters: &str) {
+        let mut letters = letters.chars();
+
+        if let Some(first) = letters.next() {
+            let position = self.active_rune(first);
+            self.play(InputFrame { pointer: Pointer::Press(position), ..default() });
+        }
+        self.drag_through_more(letters.as_str());
+    }
+
+    // Carries on a drag that's already held down
+    pub fn drag_through_more(&mut self, letters: &str) {
+        for c in letters.chars() {
+            let position = self.active_rune(c);
+            self.play(InputFrame { pointer: Pointer::Drag(position), ..default() });
+        }
+    }
+
+    fn active_rune(&mut self, letter: char) -> Vec2 {
+        self.rune_position(letter.to_ascii_uppercase())
+            .unwrap_or_else(|| panic!("no rune {} on the ring", letter))
+    }
+
+    pub fn release(&mut self) {
+        let position = self.app.world.resource::<MousePosition>().pos.unwrap_or_default();
+        self.play(InputFrame { pointer: Pointer::Release(position), ..default() });
+        self.settle();
+    }
+
+    pub fn click_word(&mut self, word: &str) {
+        self.try_click_word(word).unwrap();
+    }
+
+    // Plays the current level the way a player would, tapping out each ring's target word in turn,
+    // then waits out the reveal until the puzzle is complete
+    pub fn solve_current_level(&mut self) -> Result<(), String> {
+        let targets = self.selection().complete_solution.clone();
+
+        for (ring, word) in targets.iter().enumerate() {
+            self.try_click_word(word).map_err(|err| format!("ring {}: {}", ring, err))?;
+
+            if self.selection().current_layer as usize != ring + 1 {
+                return Err(format!("ring {}: {} was traced as {} and not accepted", ring, word, self.selection().built_word));
+            }
+        }
+
+        if self.state() != GameState::DemonReveal {
+            return Err(format!("all rings solved but the game is in {:?}", self.state()));
+        }
+
+        let completed_before = self.events().puzzles_completed;
+        self.press(KeyCode::Enter);
+
+        for _ in 0..REVEAL_FRAME_LIMIT {
+            if self.events().puzzles_completed > completed_before {
+                self.settle();
+                return Ok(());
+            }
+            self.run_frames(1);
+        }
+
+        Err("the demon reveal never finished".to_string())
+    }
+
+    pub fn is_shaking(&mut self, letter: char) -> bool {
+        let mut letters = self.app.world.query::<(&LetterDisplay, &ShakeEffect)>();
+
+        letters.iter(&self.app.world)
+            .any(|(display, shake)| display.active && display.letter == letter && shake.is_shaking())
+    }
+
+    pub fn selection(&self) -> &WordSelection {
+        self.app.world.resource::<WordSelection>()
+    }
+
+    pub fn events(&self) -> &EventLog {
+        self.app.world.resource::<EventLog>()
+    }
+
+    pub fn state(&self) -> GameState {
+        *self.app.world.resource::<State<GameState>>().get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bot_solves_every_shipped_level() {
+        let mut sim = Simulation::new("bot");
+        let level_count = sim.app.world.resource::<PuzzlesList>().list.len();
+
+        sim.start_level(0);
+
+        for index in 0..level_count {
+            assert_eq!(sim.app.world.resource::<PuzzlesList>().current, index);
+
+            if let Err(err) = sim.solve_current_level() {
+                panic!("level {} can't be solved: {}", index, err);
+            }
+        }
+
+        assert_eq!(sim.events().puzzles_completed, level_count);
+    }
+
+    #[test]
+    fn typing_the_target_word_summons_the_demon() {
+        let mut sim = Simulation::new("typing");
+        sim.start_level(0);
+        assert_eq!(sim.state(), GameState::Playing);
+        assert_eq!(sim.selection().target_word, "mayhem");
+
+        sim.type_word("mayhem");
+
+        assert_eq!(sim.events().completed, vec![0, 1]);
+        assert!(sim.selection().solves[0].perfect);
+        assert_eq!(sim.state(), GameState::DemonReveal);
+    }
+
+    #[test]
+    fn clicking_runes_traces_the_same_word() {
+        let mut sim = Simulation::new("clicking");
+        sim.start_level(0);
+
+        sim.click_word("mayh");
+        assert_eq!(sim.selection().built_word, "MAYH");
+        assert_eq!(sim.selection().positions.len(), 4);
+
+        sim.click_word("em");
+        assert_eq!(sim.selection().solves.len(), 1);
+        assert_eq!(sim.state(), GameState::DemonReveal);
+    }
+
+    #[test]
+    fn held_presses_trace_until_released() {
+        let mut sim = Simulation::new("held");
+        sim.start_level(0);
+
+        sim.drag_through("may");
+        assert!(sim.selection().tracing);
+        assert_eq!(sim.selection().built_word, "MAY");
+
+        sim.release();
+        assert!(!sim.selection().tracing);
+    }
+
+    #[test]
+    fn dragging_through_the_runes_traces_a_word() {
+        let mut sim = Simulation::new("drag");
+        sim.start_level(0);
+
+        sim.drag_through("mayhem");
+        sim.settle();
+
+        assert_eq!(sim.selection().solves.len(), 1);
+        assert!(sim.selection().solves[0].perfect);
+        assert!(sim.events().rejected.is_empty());
+        assert_eq!(sim.state(), GameState::DemonReveal);
+    }
+
+    #[test]
+    fn dragging_back_over_the_previous_rune_undoes_it() {
+        let mut sim = Simulation::new("drag-back");
+        sim.start_level(0);
+
+        sim.drag_through("mayh");
+        assert_eq!(sim.selection().built_word, "MAYH");
+
+        sim.drag_through_more("y");
+        assert_eq!(sim.selection().built_word, "MAY");
+        assert_eq!(sim.selection().positions.len(), 3);
+
+        sim.drag_through_more("hem");
+        sim.settle();
+        assert_eq!(sim.state(), GameState::DemonReveal);
+    }
+
+    #[test]
+    fn hovering_over_a_refused_rune_shakes_it_once() {
+        let mut sim = Simulation::new("drag-refused");
+        sim.start_level(0);
+
+        sim.drag_through("may");
+        sim.drag_through_more("m");
+        assert!(sim.is_shaking('M'));
+
+        // still held over the used rune long after its shake has played out
+        for _ in 0..10 {
+            sim.drag_through_more("m");
+        }
+
+        assert!(!sim.is_shaking('M'));
+        assert_eq!(sim.selection().built_word, "MAY");
+        assert!(sim.events().rejected.is_empty());
+    }
+
+    #[test]
+    fn letting_go_of_a_partial_word_is_a_miss() {
+        let mut sim = Simulation::new("drag-release");
+        sim.start_level(0);
+
+        sim.drag_through("may");
+        sim.release();
+
+        assert_eq!(sim.events().rejected, vec![("MAY".to_string(), RejectReason::BrokenRule(RuleViolation::WrongLength { length: 3, expected: 6 }))]);
+        assert_eq!(sim.selection().misses, vec![1]);
+        assert!(sim.selection().built_word.is_empty());
+        assert_eq!(sim.state(), GameState::Playing);
+    }
+
+    #[test]
+    fn time_in_the_pause_menu_is_not_scored() {
+        let mut sim = Simulation::new("paused");
+        sim.start_level(0);
+        sim.type_word("may");
+
+        sim.press(KeyCode::Escape);
+        sim.settle();
+        assert_eq!(sim.state(), GameState::Paused);
+
+        // ten seconds in the menu, far past the ring's par
+        sim.run_frames(200);
+        sim.press(KeyCode::Escape);
+        sim.settle();
+        assert_eq!(sim.state(), GameState::Playing);
+
+        sim.type_word("hem");
+
+        let seconds = sim.selection().solves[0].seconds;
+        assert!(seconds < 2., "ring took {} seconds", seconds);
+    }
+
+    #[test]
+    fn wrong_words_are_rejected_and_counted() {
+        let mut sim = Simulation::new("rejected");
+        sim.start_level(0);
+
+        sim.type_word("maehym");
+
+        assert_eq!(sim.events().rejected, vec![("MAEHYM".to_string(), RejectReason::NotAWord)]);
+        assert_eq!(sim.selection().misses, vec![1]);
+        assert_eq!(sim.state(), GameState::Playing);
+
+        sim.press(KeyCode::Backspace);
+        assert_eq!(sim.selection().built_word, "MAEHY");
+    }
+
+    #[test]
+    fn dead_ends_stay_unless_the_rules_clear_them() {
+        let mut sim = Simulation::new("dead-end");
+        sim.start_level(0);
+
+        sim.type_word("mh");
+        sim.run_frames(40);
+        assert!(sim.selection().dead_end);
+        assert_eq!(sim.selection().built_word, "MH");
+
+        let rules = SelectionRules { clear_dead_ends_after: Some(0.5), ..*sim.app.world.resource::<SelectionRules>() };
+        sim.app.world.insert_resource(rules);
+        // half a second at 20 frames a second, and a frame for the cleared trace to be checked
+        sim.run_frames(12);
+
+        assert!(sim.selection().built_word.is_empty());
+        assert!(sim.selection().positions.is_empty());
+        assert!(!sim.selection().dead_end);
+        assert!(sim.events().rejected.is_empty());
+    }
+
+    #[test]
+    fn later_rings_wake_up_after_the_first_is_solved() {
+        let mut sim = Simulation::new("rings");
+        sim.start_level(3);
+
+        sim.type_word("neuron");
+        assert_eq!(sim.selection().current_layer, 1);
+        assert_eq!(sim.selection().target_word, "scraps");
+        assert!(sim.selection().built_word.is_empty());
+
+        sim.type_word("scraps");
+        assert_eq!(sim.events().completed, vec![0, 1, 2]);
+        assert_eq!(sim.state(), GameState::DemonReveal);
+    }
+
+    #[test]
+    fn level_select_shows_beaten_levels_without_demon_art() {
+        let mut sim = Simulation::new("level-select");
+        sim.start_level(0);
+        sim.type_word("mayhem");
+
+        // headless there are no demon sprites to put behind the beaten level
+        sim.app.world.resource_mut::<NextState<GameState>>().set(GameState::LevelSelect);
+        sim.run_frames(2);
+
+        assert_eq!(sim.state(), GameState::LevelSelect);
+    }
+
+    #[test]
+    fn the_reveal_moves_on_to_the_next_level() {
+        let mut sim = Simulation::new("next-level");
+        sim.start_level(0);
+        sim.type_word("mayhem");
+
+        sim.press(KeyCode::Enter);
+        // the demon takes a second to fill the screen
+        sim.run_frames(30);
+
+        assert_eq!(sim.events().puzzles_completed, 1);
+        assert_eq!(sim.state(), GameState::Playing);
+        assert_eq!(sim.app.world.resource::<PuzzlesList>().current, 1);
+        assert_eq!(sim.selection().target_word, "entice");
+        assert!(sim.selection().solves.is_empty());
+    }
+}
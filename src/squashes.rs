@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use std::f32::consts::PI;
 
 #[derive(Component)]
 pub struct SquishEffect {
@@ -21,6 +22,31 @@ impl SquishEffect {
     }
 }
 
+#[derive(Component)]
+pub struct ShakeEffect {
+    duration: f32,
+    elapsed: f32,
+    magnitude: f32,
+    frequency: f32,
+    base_translation: Vec3,
+}
+
+impl ShakeEffect {
+    pub fn new(base_translation: Vec3, magnitude: f32, frequency: f32, duration: f32) -> ShakeEffect {
+        // unlike squishes, shakes start idle and only play when reset
+        ShakeEffect { duration, elapsed: duration, magnitude, frequency, base_translation }
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = 0.0;
+    }
+
+    #[cfg(test)]
+    pub fn is_shaking(&self) -> bool {
+        self.elapsed < self.duration
+    }
+}
+
 pub fn squish_effects(mut squishees: Query<(&mut Transform, &mut SquishEffect)>, time: Res<Time>) {
     for (mut transform, mut squish) in squishees.iter_mut() {
         if squish.elapsed < squish.total_time {
@@ -55,4 +81,22 @@ pub fn squish_effects(mut squishees: Query<(&mut Transform, &mut SquishEffect)>,
             
         }
     }
-} 
\ No newline at end of file
+}
+
+pub fn shake_effects(mut shakers: Query<(&mut Transform, &mut ShakeEffect)>, time: Res<Time>) {
+    for (mut transform, mut shake) in shakers.iter_mut() {
+        if shake.elapsed < shake.duration {
+            shake.elapsed += time.delta_seconds();
+
+            if shake.elapsed >= shake.duration {
+                // animation finished, settle back into place
+                transform.translation = shake.base_translation;
+            }
+            else {
+                let falloff = 1.0 - shake.elapsed / shake.duration;
+                let offset = (shake.elapsed * shake.frequency * 2. * PI).sin() * shake.magnitude * falloff;
+                transform.translation = shake.base_translation + Vec3::X * offset;
+            }
+        }
+    }
+}
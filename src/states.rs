@@ -0,0 +1,258 @@
+use bevy::prelude::*;
+
+use crate::daily::*;
+use crate::puzzles::*;
+use crate::save::*;
+use crate::{DemonArts, MousePosition, RuneTextStyles};
+
+#[derive(States, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum GameState {
+    #[default]
+    MainMenu,
+    LevelSelect,
+    Playing,
+    DemonReveal,
+    Paused,
+    Credits,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MenuAction {
+    Play,
+    LevelSelect,
+    Credits,
+    MainMenu,
+    Resume,
+    Daily,
+    StartLevel(usize),
+}
+
+// Everything spawned for a menu screen, cleared when leaving it
+#[derive(Component)]
+pub struct MenuObject {}
+
+#[derive(Component)]
+pub struct MenuButton {
+    pub action: MenuAction,
+    pub half_size: Vec2,
+}
+
+const MENU_CENTER: Vec2 = Vec2::new(0., -50.);
+
+pub fn spawn_menu_text(commands: &mut Commands, text: &str, style: &TextStyle, position: Vec2) -> Entity {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(text, style.clone()),
+            transform: Transform::from_translation(position.extend(1.)),
+            ..default()
+        },
+        MenuObject {},
+    )).id()
+}
+
+pub fn spawn_menu_button(commands: &mut Commands, text: &str, style: &TextStyle, position: Vec2, action: MenuAction) -> Entity {
+    let entity = spawn_menu_text(commands, text, style, position);
+
+    commands.entity(entity).insert(MenuButton {
+        action,
+        half_size: Vec2::new(style.font_size * 0.3 * text.len() as f32 + 10., style.font_size * 0.5),
+    });
+
+    entity
+}
+
+pub fn spawn_main_menu(
+    mut commands: Commands,
+    rune_fonts: Res<RuneTextStyles>,
+    save_data: Res<SaveData>,
+) {
+    spawn_menu_text(&mut commands, "SUMMONERS WORD", &rune_fonts.display, MENU_CENTER + Vec2::new(0., 200.));
+
+    spawn_menu_button(&mut commands, "PLAY", &rune_fonts.active, MENU_CENTER + Vec2::new(0., 80.), MenuAction::Play);
+
+    // one go at the daily level, after that it stays locked until tomorrow's comes round
+    let day = today();
+    if save_data.daily.played_on(day) {
+        let played_style = TextStyle { color: Color::GRAY, ..rune_fonts.active.clone() };
+        spawn_menu_text(&mut commands, "DAILY DONE", &played_style, MENU_CENTER);
+    }
+    else {
+        spawn_menu_button(&mut commands, "DAILY", &rune_fonts.active, MENU_CENTER, MenuAction::Daily);
+    }
+
+    spawn_menu_button(&mut commands, "LEVELS", &rune_fonts.active, MENU_CENTER + Vec2::new(0., -80.), MenuAction::LevelSelect);
+    spawn_menu_button(&mut commands, "CREDITS", &rune_fonts.active, MENU_CENTER + Vec2::new(0., -160.), MenuAction::Credits);
+
+    let streak = save_data.daily.current_streak(day);
+    if streak > 0 {
+        let streak_text = format!("DAILY STREAK {}  BEST {}", streak, save_data.daily.best_streak);
+        spawn_menu_text(&mut commands, &streak_text, &rune_fonts.active, MENU_CENTER + Vec2::new(0., -260.));
+    }
+}
+
+pub fn spawn_level_select(
+    mut commands: Commands,
+    rune_fonts: Res<RuneTextStyles>,
+    puzzles_list: Res<PuzzlesList>,
+    save_data: Res<SaveData>,
+    demons: Res<DemonArts>,
+) {
+    spawn_menu_text(&mut commands, "LEVELS", &rune_fonts.display, MENU_CENTER + Vec2::new(0., 280.));
+
+    let columns = 5;
+    for (index, level) in puzzles_list.list.iter().enumerate() {
+        let column = (index % columns) as f32 - (columns - 1) as f32 / 2.;
+        let row = (index / columns) as f32;
+        let position = MENU_CENTER + Vec2::new(column * 100., 180. - row * 100.);
+        let label = (index + 1).to_string();
+
+        if !save_data.is_unlocked(&puzzles_list.list, index) {
+            spawn_menu_text(&mut commands, &label, &rune_fonts.idle, position);
+            continue;
+        }
+
+        // beaten levels show the demon that was summoned behind their number
+        if save_data.is_completed(level) {
+            commands.spawn((
+                SpriteBundle {
+                    texture: demons.sprites.get(level.demon).cloned().unwrap_or_default(),
+                    sprite: Sprite {
+                        color: Color::rgba(1., 1., 1., 0.6),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(position.extend(0.5)).with_scale(Vec3::splat(4.)),
+                    ..default()
+                },
+                MenuObject {},
+            ));
+        }
+
+        spawn_menu_button(&mut commands, &label, &rune_fonts.active, position, MenuAction::StartLevel(index));
+    }
+
+    spawn_menu_button(&mut commands, "BACK", &rune_fonts.active, MENU_CENTER + Vec2::new(0., -280.), MenuAction::MainMenu);
+}
+
+pub fn spawn_credits(
+    mut commands: Commands,
+    rune_fonts: Res<RuneTextStyles>,
+) {
+    spawn_menu_text(&mut commands, "CREDITS", &rune_fonts.display, MENU_CENTER + Vec2::new(0., 200.));
+
+    let lines = [
+        "Made for Ludum Dare 55",
+        "Fonts: Micro 5, SGA pixel",
+        "Thanks for playing",
+    ];
+    for (i, line) in lines.iter().enumerate() {
+        spawn_menu_text(&mut commands, line, &rune_fonts.active, MENU_CENTER + Vec2::new(0., 80. - i as f32 * 60.));
+    }
+
+    spawn_menu_button(&mut commands, "BACK", &rune_fonts.active, MENU_CENTER + Vec2::new(0., -200.), MenuAction::MainMenu);
+}
+
+pub fn spawn_pause_menu(
+    mut commands: Commands,
+    rune_fonts: Res<RuneTextStyles>,
+) {
+    // darken the level underneath rather than clearing it, it's still there when resuming
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(0., 0., 0., 0.85),
+                custom_size: Some(Vec2::splat(2000.)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(0., 0., 0.5)),
+            ..default()
+        },
+        MenuObject {},
+    ));
+
+    spawn_menu_text(&mut commands, "PAUSED", &rune_fonts.display, MENU_CENTER + Vec2::new(0., 120.));
+
+    spawn_menu_button(&mut commands, "RESUME", &rune_fonts.active, MENU_CENTER, MenuAction::Resume);
+    spawn_menu_button(&mut commands, "MENU", &rune_fonts.active, MENU_CENTER + Vec2::new(0., -80.), MenuAction::MainMenu);
+}
+
+pub fn despawn_menu(
+    mut commands: Commands,
+    menu_entities: Query<Entity, With<MenuObject>>,
+) {
+    for entity in menu_entities.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn handle_menu_buttons(
+    buttons: Query<(&Transform, &MenuButton)>,
+    mouse_pos: Res<MousePosition>,
+    mut puzzles_list: ResMut<PuzzlesList>,
+    mut mode: ResMut<PlayMode>,
+    save_data: Res<SaveData>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !mouse_pos.just_clicked {
+        return;
+    }
+
+    let Some(pos) = mouse_pos.pos else {
+        return;
+    };
+
+    for (transform, button) in buttons.iter() {
+        let offset = (pos - transform.translation.truncate()).abs();
+        if offset.x > button.half_size.x || offset.y > button.half_size.y {
+            continue;
+        }
+
+        match button.action {
+            MenuAction::Play => {
+                *mode = PlayMode::Campaign;
+                next_state.set(GameState::Playing);
+            }
+            MenuAction::Resume => next_state.set(GameState::Playing),
+            MenuAction::LevelSelect => next_state.set(GameState::LevelSelect),
+            MenuAction::Credits => next_state.set(GameState::Credits),
+            MenuAction::MainMenu => next_state.set(GameState::MainMenu),
+            MenuAction::Daily => {
+                // the menu may have been open since yesterday, check again
+                let day = today();
+                if !save_data.daily.played_on(day) {
+                    *mode = PlayMode::daily(day);
+                    next_state.set(GameState::Playing);
+                }
+            }
+            MenuAction::StartLevel(index) => {
+                *mode = PlayMode::Campaign;
+                puzzles_list.current = index;
+                next_state.set(GameState::Playing);
+            }
+        }
+    }
+}
+
+pub fn leave_menu(
+    keys: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !keys.just_pressed(KeyCode::Escape) {
+        return;
+    }
+
+    match state.get() {
+        GameState::Paused => next_state.set(GameState::Playing),
+        GameState::LevelSelect | GameState::Credits => next_state.set(GameState::MainMenu),
+        _ => {}
+    }
+}
+
+pub fn pause_game(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        next_state.set(GameState::Paused);
+    }
+}
@@ -0,0 +1,200 @@
+use puzzle_core::*;
+use std::collections::HashMap;
+use std::fs;
+use std::str::FromStr;
+
+use crate::ambiguity::*;
+use crate::codes::*;
+use crate::generator::*;
+use crate::puzzles::*;
+use crate::worldlist::*;
+
+// Command line tools that run instead of the game, e.g. `summoners-word validate <pack>`.
+// Returns the process exit code if the arguments named a tool.
+pub fn run_tool(args: &[String]) -> Option<i32> {
+    let tool_args = args.get(2..).unwrap_or_default();
+
+    let result = match args.get(1).map(String::as_str) {
+        Some("validate") => validate(tool_args),
+        Some("generate") => generate(tool_args),
+        Some("ambiguity") => ambiguity(tool_args),
+        Some("codes") => codes(tool_args),
+        Some("decode") => decode(tool_args),
+        _ => return None,
+    };
+
+    match result {
+        Ok(()) => Some(0),
+        Err(err) => {
+            println!("{}", err);
+            Some(1)
+        }
+    }
+}
+
+// Reads `--name value` style options, anything else is left for the tool to handle
+fn parse_options(args: &[String]) -> Result<(HashMap<String, String>, Vec<String>), String> {
+    let mut options = HashMap::new();
+    let mut positional = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if let Some(name) = arg.strip_prefix("--") {
+            let value = iter.next().ok_or(format!("--{} needs a value", name))?;
+            options.insert(name.to_string(), value.clone());
+        }
+        else {
+            positional.push(arg.clone());
+        }
+    }
+
+    Ok((options, positional))
+}
+
+fn number_option<T: FromStr>(options: &HashMap<String, String>, name: &str, default: T) -> Result<T, String> {
+    match options.get(name) {
+        Some(value) => value.parse().map_err(|_| format!("--{} expects a number, got {}", name, value)),
+        None => Ok(default),
+    }
+}
+
+fn read_pack_file(path: &str) -> Result<PuzzlePack, String> {
+    let contents = fs::read(path).map_err(|err| format!("{}: {}", path, err))?;
+    ron::de::from_bytes::<PuzzlePack>(&contents).map_err(|err| format!("{}: {}", path, err))
+}
+
+// Prints a pack's problems the way `validate` does and returns how many there were
+fn report_issues(path: &str, pack: &PuzzlePack) -> usize {
+    let issues = validate_pack(pack, DEMON_SPRITES.len());
+    for issue in issues.iter() {
+        println!("{}: {}", path, issue);
+    }
+
+    issues.len()
+}
+
+// For tools that work on the levels themselves, which only make sense for a valid pack
+fn read_valid_pack_file(path: &str) -> Result<PuzzlePack, String> {
+    let pack = read_pack_file(path)?;
+
+    match report_issues(path, &pack) {
+        0 => Ok(pack),
+        count => Err(format!("{}: {} problems need fixing first", path, count)),
+    }
+}
+
+fn default_pack_path() -> String {
+    format!("assets/{}", DEFAULT_PUZZLE_PACK)
+}
+
+fn validate(args: &[String]) -> Result<(), String> {
+    let paths = if args.is_empty() { vec![default_pack_path()] } else { args.to_vec() };
+
+    let mut failed_packs = 0;
+
+    for path in paths.iter() {
+        let pack = match read_pack_file(path) {
+            Ok(pack) => pack,
+            Err(err) => {
+                println!("{}", err);
+                failed_packs += 1;
+                continue;
+            }
+        };
+
+        if report_issues(path, &pack) == 0 {
+            println!("{}: {} levels ok", path, pack.levels.len());
+        }
+        else {
+            failed_packs += 1;
+        }
+    }
+
+    if failed_packs > 0 {
+        return Err(format!("{} of {} puzzle packs failed validation", failed_packs, paths.len()));
+    }
+
+    Ok(())
+}
+
+fn generate(args: &[String]) -> Result<(), String> {
+    let (options, _) = parse_options(args)?;
+
+    let defaults = GeneratorSettings::default();
+    let seed = number_option(&options, "seed", rand::random::<u64>())?;
+    let levels = number_option(&options, "levels", 1)?;
+    let settings = GeneratorSettings {
+        rings: number_option(&options, "rings", defaults.rings)?,
+        min_length: number_option(&options, "min", defaults.min_length)?,
+        max_length: number_option(&options, "max", defaults.max_length)?,
+        demon_count: defaults.demon_count,
+    };
+    settings.check()?;
+
+    let pack = generate_pack(seed, levels, &settings, &WordBuckets::default())?;
+
+    let text = ron::ser::to_string_pretty(&pack, ron::ser::PrettyConfig::default())
+        .map_err(|err| format!("could not write puzzle pack: {}", err))?;
+    println!("{}", text);
+
+    Ok(())
+}
+
+fn ambiguity(args: &[String]) -> Result<(), String> {
+    let (options, paths) = parse_options(args)?;
+    let max_alternates: Option<usize> = match options.get("max") {
+        Some(_) => Some(number_option(&options, "max", 0)?),
+        None => None,
+    };
+
+    let path = paths.first().cloned().unwrap_or_else(default_pack_path);
+    let pack = read_valid_pack_file(&path)?;
+    let word_list = shipped_word_list(&pack.rules);
+
+    let mut flagged = 0;
+
+    for ring in ambiguity_report(&pack, &word_list) {
+        let too_many = max_alternates.is_some_and(|max| ring.alternates.len() > max);
+        if too_many {
+            flagged += 1;
+        }
+
+        let summary = if ring.alternates.is_empty() {
+            "no alternates".to_string()
+        }
+        else {
+            format!("{} alternates: {}", ring.alternates.len(), ring.alternates.join(" "))
+        };
+
+        println!("{}level {} ring {} \"{}\": {}", if too_many { "!! " } else { "" }, ring.level, ring.ring, ring.target, summary);
+    }
+
+    if flagged > 0 {
+        return Err(format!("{} rings have more than {} alternate solutions", flagged, max_alternates.unwrap_or_default()));
+    }
+
+    Ok(())
+}
+
+fn codes(args: &[String]) -> Result<(), String> {
+    let path = args.first().cloned().unwrap_or_else(default_pack_path);
+    let pack = read_valid_pack_file(&path)?;
+
+    for (index, level) in pack.levels.iter().enumerate() {
+        let code = encode_level(level).map_err(|err| format!("level {}: {}", index, err))?;
+        println!("level {}: {}", index, code);
+    }
+
+    Ok(())
+}
+
+fn decode(args: &[String]) -> Result<(), String> {
+    let code = args.first().ok_or("decode needs a share code")?;
+    let level = decode_level(code).map_err(|err| err.to_string())?;
+
+    let text = ron::ser::to_string_pretty(&level, ron::ser::PrettyConfig::default())
+        .map_err(|err| format!("could not write level: {}", err))?;
+    println!("{}", text);
+
+    Ok(())
+}
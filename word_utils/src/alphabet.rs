@@ -0,0 +1,67 @@
+pub fn alphabet_index(letter: u8) -> usize {
+    if letter.is_ascii_uppercase() {
+        letter as usize - 'A' as usize
+    }
+    else if letter.is_ascii_lowercase() {
+        letter as usize - 'a' as usize   
+    }
+    else {
+        panic!("not a letter");
+    }
+}
+
+pub fn word_to_bits(word: &str) -> u32 {
+    let mut val = 0;
+
+    for c in word.as_bytes() {
+        val |= 1 << alphabet_index(*c);
+    }
+
+    val
+}
+
+pub fn bits_to_letters(bits: u32) -> String {
+    let mut s = String::new();
+
+    for c in 'A'..='Z' {
+        if bits & 1 << alphabet_index(c as u8) != 0 {
+            s.push(c);
+        }
+    }
+
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn letters_index_from_a() {
+        assert_eq!(alphabet_index(b'A'), 0);
+        assert_eq!(alphabet_index(b'z'), 25);
+        assert_eq!(bits_to_letters(word_to_bits("mayhem")), "AEHMY");
+    }
+
+    proptest! {
+        #[test]
+        fn bits_round_trip_through_letters(bits in 0u32..1 << 26) {
+            prop_assert_eq!(word_to_bits(&bits_to_letters(bits)), bits);
+        }
+
+        #[test]
+        fn letters_come_back_sorted_and_distinct(word in "[a-zA-Z]{0,20}") {
+            let mut expected: Vec<char> = word.to_ascii_uppercase().chars().collect();
+            expected.sort();
+            expected.dedup();
+
+            prop_assert_eq!(bits_to_letters(word_to_bits(&word)), expected.into_iter().collect::<String>());
+        }
+
+        #[test]
+        fn case_does_not_matter(word in "[a-zA-Z]{0,20}") {
+            prop_assert_eq!(word_to_bits(&word), word_to_bits(&word.to_ascii_lowercase()));
+        }
+    }
+}
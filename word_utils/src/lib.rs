@@ -0,0 +1,8 @@
+// Letters and words the way the game and the word_filter tool both see them: sets of letters packed
+// into bits, and the one rule for which dictionary words can be played as a ring.
+
+mod alphabet;
+mod words;
+
+pub use alphabet::*;
+pub use words::*;
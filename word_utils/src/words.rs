@@ -0,0 +1,99 @@
+use crate::alphabet::*;
+
+// Fewest runes a ring can have, so the shortest playable word is one letter longer
+pub const MIN_RING_RUNES: usize = 4;
+
+// Whether a dictionary word can be traced as a loop at all: only the letters A to Z, longer than the
+// smallest ring and ending on the letter it starts with. The ends are compared as written, so proper
+// nouns like Alaska don't close.
+pub fn is_loop_word(word: &str) -> bool {
+    let bytes = word.as_bytes();
+
+    if bytes.len() <= MIN_RING_RUNES || !bytes.iter().all(u8::is_ascii_alphabetic) {
+        return false;
+    }
+
+    bytes[0] == bytes[bytes.len() - 1]
+}
+
+// Whether a dictionary word can be a ring: a loop with no letter used twice before the closing one,
+// since every rune on a ring is different
+pub fn is_valid_word(word: &str) -> bool {
+    // the closing letter is the first one again, so it doesn't add to the count
+    is_loop_word(word) && word_to_bits(word).count_ones() as usize == word.len() - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use proptest::sample::subsequence;
+
+    fn alphabet() -> Vec<char> {
+        ('a'..='z').collect()
+    }
+
+    // any set of distinct letters, shuffled and looped back to the first
+    fn ring_word() -> impl Strategy<Value = String> {
+        subsequence(alphabet(), MIN_RING_RUNES..=12)
+            .prop_shuffle()
+            .prop_map(|letters| letters.iter().chain(letters.first()).collect())
+    }
+
+    #[test]
+    fn shipped_style_words() {
+        assert!(is_valid_word("mayhem"));
+        assert!(is_valid_word("ASPCA"));
+        assert!(!is_valid_word("Alaska"));
+        assert!(!is_valid_word("dyed"));
+        assert!(!is_valid_word("teetht"));
+        assert!(!is_valid_word("rocks"));
+        assert!(!is_valid_word("may-m"));
+    }
+
+    #[test]
+    fn loops_may_repeat_letters() {
+        assert!(is_loop_word("teetht"));
+        assert!(is_loop_word("mayhem"));
+        assert!(!is_loop_word("dyed"));
+        assert!(!is_loop_word("Alaska"));
+    }
+
+    proptest! {
+        #[test]
+        fn loops_of_distinct_letters_are_valid(word in ring_word()) {
+            prop_assert!(is_valid_word(&word));
+            prop_assert!(is_valid_word(&word.to_ascii_uppercase()));
+        }
+
+        #[test]
+        fn matches_the_rule_spelled_out(word in "[a-zA-Z]{0,10}") {
+            let closed = word.len() > MIN_RING_RUNES && word.chars().next() == word.chars().last();
+
+            let lower = word.to_ascii_lowercase();
+            let interior: Vec<char> = lower.chars().take(lower.len().saturating_sub(1)).collect();
+            let distinct = interior.iter().enumerate().all(|(i, c)| !interior[..i].contains(c));
+
+            prop_assert_eq!(is_loop_word(&word), closed);
+            prop_assert_eq!(is_valid_word(&word), closed && distinct);
+        }
+
+        #[test]
+        fn repeating_a_letter_breaks_the_ring(word in ring_word(), index in any::<prop::sample::Index>()) {
+            let interior = &word[..word.len() - 1];
+            let repeated = interior.as_bytes()[index.index(interior.len())] as char;
+            let longer = format!("{}{}{}", interior, repeated, &word[word.len() - 1..]);
+
+            prop_assert!(!is_valid_word(&longer));
+            prop_assert!(is_loop_word(&longer));
+        }
+
+        #[test]
+        fn anything_but_letters_is_refused(word in ring_word(), index in any::<prop::sample::Index>(), symbol in "[^a-zA-Z]") {
+            let mut broken = word.clone();
+            broken.insert_str(index.index(word.len() + 1), &symbol);
+
+            prop_assert!(!is_valid_word(&broken));
+        }
+    }
+}